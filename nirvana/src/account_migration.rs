@@ -0,0 +1,500 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{AccountSerialize, Discriminator};
+
+use crate::errors::ErrorCode;
+use crate::numbers::IndexNumber;
+use crate::state::{
+    FeeCollector, GlobalHistory, MoneyMarket, NirvCenterConfigV3, TranaMeta, UserRewardV2,
+    UserTranaContract,
+};
+
+/// Accounts as they were laid out before their indices widened or their fields grew
+///
+/// Only the layout matters, these are never written again.
+pub mod legacy {
+    use crate::numbers::{ArbitraryNumber, CoarseNumber, PreciseNumber, ALMS, ANA, NIRV};
+    use anchor_lang::prelude::*;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct UserRewardV2 {
+        pub index: PreciseNumber,
+        pub owner: Pubkey,
+        pub staked_amount: ANA,
+        pub borrowed_nirv: NIRV,
+        pub staged_pre_ana_rewards: ANA,
+        pub staged_pre_ana_fees: ANA,
+        pub bump: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct FeeCollector {
+        pub nirv_fee_index: PreciseNumber,
+        pub ana_fee_index: PreciseNumber,
+        pub prana_fee_index: PreciseNumber,
+        pub owner: Pubkey,
+        pub staked_alms: ALMS,
+        pub staged_ana: ANA,
+        pub staged_nirv: NIRV,
+        pub staged_pre_ana: ANA,
+        pub bump: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct NirvCenterConfigV3 {
+        pub mint_ana: Pubkey,
+        pub mint_pre_ana: Pubkey,
+        pub mint_nirv: Pubkey,
+        pub mint_alms: Pubkey,
+        pub treasury_ana: Pubkey,
+        pub ana_fee_account: Pubkey,
+        pub prana_fee_account: Pubkey,
+        pub nirv_fee_account: Pubkey,
+        pub nirv_fee_index: PreciseNumber,
+        pub ana_fee_index: PreciseNumber,
+        pub prana_fee_index: PreciseNumber,
+        pub reward_index: PreciseNumber,
+        pub stake_pool_ana: Pubkey,
+        pub stake_pool_alms: Pubkey,
+        pub prana_reward_rate: PreciseNumber,
+        pub prana_reward_interval_seconds: i64,
+        pub time_of_last_prana_reward: u64,
+        pub bs_start_offset: PreciseNumber,
+        pub bs_duration_seconds: u64,
+        pub bs_start_time_seconds: u64,
+        pub current_ana_price_usd: PreciseNumber,
+        pub unstake_fee: CoarseNumber,
+        pub sell_fee: CoarseNumber,
+        pub instant_buy_fee: CoarseNumber,
+        pub trana_buy_fee: CoarseNumber,
+        pub nirv_loan_origination_fee: CoarseNumber,
+        pub nirv_debt_fee: CoarseNumber,
+        pub max_prana_per_hour: u64,
+        pub max_nirv_loan_ratio: CoarseNumber,
+        pub price_curve: Pubkey,
+        pub bump: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct MoneyMarket {
+        pub nirv_center: Pubkey,
+        pub for_amm: bool,
+        pub for_prana: bool,
+        pub for_trana: bool,
+        pub enabled: bool,
+        pub risk_free_value_coefficient: CoarseNumber,
+        pub pyth_oracle_price: Pubkey,
+        pub pyth_oracle_metadata: Pubkey,
+        pub mint: Pubkey,
+        pub decimals: u8,
+        pub token_account: Pubkey,
+        pub bump: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct GlobalHistory {
+        pub volume_usd: u64,
+        pub net_purchased_ana: ANA,
+        pub all_time_high_ana_supply: ANA,
+        pub prana_minted: ANA,
+        pub total_prana_rewards: ANA,
+        pub staged_prana_rewards: ANA,
+        pub prana_executed: ANA,
+        pub prana_purchased: ANA,
+        pub nirv_minted: NIRV,
+        pub nirv_repaid: NIRV,
+        pub bump: u8,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct TranaMeta {
+        pub nirv_center: Pubkey,
+        pub enabled: bool,
+        pub sensitivity: PreciseNumber,
+        pub max_discount_ratio: PreciseNumber,
+        pub money_market: Pubkey,
+        pub ana_outstanding: ANA,
+        pub total_bought: ArbitraryNumber,
+        pub vesting_length_seconds: u64,
+    }
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Default)]
+    pub struct UserTranaContract {
+        pub user: Pubkey,
+        pub available: bool,
+        pub trana_meta: Pubkey,
+        pub amount_ana: ANA,
+        pub redeemed_amount: ANA,
+        pub price_in_underlying: ArbitraryNumber,
+        pub start_time: i64,
+        pub end_time: i64,
+    }
+}
+
+impl From<legacy::UserRewardV2> for UserRewardV2 {
+    fn from(old: legacy::UserRewardV2) -> Self {
+        UserRewardV2 {
+            index: IndexNumber::from_precise(old.index),
+            owner: old.owner,
+            staked_amount: old.staked_amount,
+            borrowed_nirv: old.borrowed_nirv,
+            staged_pre_ana_rewards: old.staged_pre_ana_rewards,
+            staged_pre_ana_fees: old.staged_pre_ana_fees,
+            bump: old.bump,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<legacy::FeeCollector> for FeeCollector {
+    fn from(old: legacy::FeeCollector) -> Self {
+        FeeCollector {
+            nirv_fee_index: IndexNumber::from_precise(old.nirv_fee_index),
+            ana_fee_index: IndexNumber::from_precise(old.ana_fee_index),
+            prana_fee_index: IndexNumber::from_precise(old.prana_fee_index),
+            owner: old.owner,
+            staked_alms: old.staked_alms,
+            staged_ana: old.staged_ana,
+            staged_nirv: old.staged_nirv,
+            staged_pre_ana: old.staged_pre_ana,
+            bump: old.bump,
+        }
+    }
+}
+
+impl From<legacy::NirvCenterConfigV3> for NirvCenterConfigV3 {
+    /// Realizing prANA with NIRV stays open, as before
+    fn from(old: legacy::NirvCenterConfigV3) -> Self {
+        NirvCenterConfigV3 {
+            mint_ana: old.mint_ana,
+            mint_pre_ana: old.mint_pre_ana,
+            mint_nirv: old.mint_nirv,
+            mint_alms: old.mint_alms,
+            treasury_ana: old.treasury_ana,
+            ana_fee_account: old.ana_fee_account,
+            prana_fee_account: old.prana_fee_account,
+            nirv_fee_account: old.nirv_fee_account,
+            nirv_fee_index: IndexNumber::from_precise(old.nirv_fee_index),
+            ana_fee_index: IndexNumber::from_precise(old.ana_fee_index),
+            prana_fee_index: IndexNumber::from_precise(old.prana_fee_index),
+            reward_index: IndexNumber::from_precise(old.reward_index),
+            stake_pool_ana: old.stake_pool_ana,
+            stake_pool_alms: old.stake_pool_alms,
+            prana_reward_rate: old.prana_reward_rate,
+            prana_reward_interval_seconds: old.prana_reward_interval_seconds,
+            time_of_last_prana_reward: old.time_of_last_prana_reward,
+            bs_start_offset: old.bs_start_offset,
+            bs_duration_seconds: old.bs_duration_seconds,
+            bs_start_time_seconds: old.bs_start_time_seconds,
+            current_ana_price_usd: old.current_ana_price_usd,
+            unstake_fee: old.unstake_fee,
+            sell_fee: old.sell_fee,
+            instant_buy_fee: old.instant_buy_fee,
+            trana_buy_fee: old.trana_buy_fee,
+            nirv_loan_origination_fee: old.nirv_loan_origination_fee,
+            nirv_debt_fee: old.nirv_debt_fee,
+            max_prana_per_hour: old.max_prana_per_hour,
+            max_nirv_loan_ratio: old.max_nirv_loan_ratio,
+            price_curve: old.price_curve,
+            bump: old.bump,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<legacy::MoneyMarket> for MoneyMarket {
+    fn from(old: legacy::MoneyMarket) -> Self {
+        MoneyMarket {
+            nirv_center: old.nirv_center,
+            for_amm: old.for_amm,
+            for_prana: old.for_prana,
+            for_trana: old.for_trana,
+            enabled: old.enabled,
+            risk_free_value_coefficient: old.risk_free_value_coefficient,
+            pyth_oracle_price: old.pyth_oracle_price,
+            pyth_oracle_metadata: old.pyth_oracle_metadata,
+            mint: old.mint,
+            decimals: old.decimals,
+            token_account: old.token_account,
+            bump: old.bump,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<legacy::GlobalHistory> for GlobalHistory {
+    fn from(old: legacy::GlobalHistory) -> Self {
+        GlobalHistory {
+            volume_usd: old.volume_usd,
+            net_purchased_ana: old.net_purchased_ana,
+            all_time_high_ana_supply: old.all_time_high_ana_supply,
+            prana_minted: old.prana_minted,
+            total_prana_rewards: old.total_prana_rewards,
+            staged_prana_rewards: old.staged_prana_rewards,
+            prana_executed: old.prana_executed,
+            prana_purchased: old.prana_purchased,
+            nirv_minted: old.nirv_minted,
+            nirv_repaid: old.nirv_repaid,
+            bump: old.bump,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<legacy::TranaMeta> for TranaMeta {
+    /// Without a capacity, purchase limits, window or debt, as before
+    fn from(old: legacy::TranaMeta) -> Self {
+        TranaMeta {
+            nirv_center: old.nirv_center,
+            enabled: old.enabled,
+            sensitivity: old.sensitivity,
+            max_discount_ratio: old.max_discount_ratio,
+            money_market: old.money_market,
+            ana_outstanding: old.ana_outstanding,
+            total_bought: old.total_bought,
+            vesting_length_seconds: old.vesting_length_seconds,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<legacy::UserTranaContract> for UserTranaContract {
+    /// Bonds were only sold in debug mode before they had a token, so none is minted here
+    fn from(old: legacy::UserTranaContract) -> Self {
+        UserTranaContract {
+            user: old.user,
+            available: old.available,
+            trana_meta: old.trana_meta,
+            amount_ana: old.amount_ana,
+            redeemed_amount: old.redeemed_amount,
+            price_in_underlying: old.price_in_underlying,
+            start_time: old.start_time,
+            end_time: old.end_time,
+            ..Default::default()
+        }
+    }
+}
+
+/// Read an account written in its legacy layout
+///
+/// Accounts were sized to their layout, so any other size was already migrated.
+pub fn decode_legacy<L, T>(data: &[u8]) -> Result<T>
+where
+    L: AnchorSerialize + AnchorDeserialize + Default,
+    T: From<L>,
+{
+    if data.len() != 8 + L::default().try_to_vec()?.len() {
+        return Err(ErrorCode::AccountAlreadyMigrated.into());
+    }
+
+    let old = L::deserialize(&mut &data[8..])?;
+
+    Ok(T::from(old))
+}
+
+/// Write an account in its current layout
+pub fn encode<T: AccountSerialize>(account: &T) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    account.try_serialize(&mut data)?;
+
+    Ok(data)
+}
+
+/// Does the account with this discriminator have a legacy layout?
+pub fn is_migratable(discriminator: &[u8]) -> bool {
+    [
+        UserRewardV2::discriminator(),
+        FeeCollector::discriminator(),
+        NirvCenterConfigV3::discriminator(),
+        MoneyMarket::discriminator(),
+        GlobalHistory::discriminator(),
+        TranaMeta::discriminator(),
+        UserTranaContract::discriminator(),
+    ]
+    .iter()
+    .any(|d| d == discriminator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::numbers::{PreciseNumber, ANA, NIRV};
+    use anchor_lang::AccountDeserialize;
+
+    /// An account as it was written in its legacy layout
+    fn legacy_bytes<T: Discriminator, L: AnchorSerialize>(old: &L) -> Vec<u8> {
+        let mut data = T::discriminator().to_vec();
+        data.extend(old.try_to_vec().unwrap());
+
+        data
+    }
+
+    #[test]
+    fn widens_user_reward() {
+        let owner = Pubkey::new_unique();
+        let old = legacy_bytes::<UserRewardV2, _>(&legacy::UserRewardV2 {
+            index: PreciseNumber::new(12),
+            owner,
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(20),
+            staged_pre_ana_rewards: ANA::new(3),
+            staged_pre_ana_fees: ANA::new(1),
+            bump: 254,
+        });
+        assert_eq!(old.len(), 81);
+
+        let migrated: UserRewardV2 = decode_legacy::<legacy::UserRewardV2, _>(&old).unwrap();
+        let new = encode(&migrated).unwrap();
+
+        let migrated = UserRewardV2::try_deserialize(&mut new.as_slice()).unwrap();
+        assert_eq!(migrated.index, IndexNumber::new(12));
+        assert_eq!(migrated.owner, owner);
+        assert_eq!(migrated.staked_amount, ANA::new(100));
+        assert_eq!(migrated.borrowed_nirv, NIRV::new(20));
+        assert_eq!(migrated.staged_pre_ana_rewards, ANA::new(3));
+        assert_eq!(migrated.staged_pre_ana_fees, ANA::new(1));
+        assert_eq!(migrated.bump, 254);
+        assert_eq!(migrated.stream_count, 0);
+
+        // once is enough
+        assert!(decode_legacy::<legacy::UserRewardV2, UserRewardV2>(&new).is_err());
+    }
+
+    #[test]
+    fn widens_fee_collector() {
+        let old = legacy_bytes::<FeeCollector, _>(&legacy::FeeCollector {
+            nirv_fee_index: PreciseNumber::new(1),
+            ana_fee_index: PreciseNumber::new(2),
+            prana_fee_index: PreciseNumber::new(3),
+            staged_nirv: NIRV::new(4),
+            bump: 252,
+            ..Default::default()
+        });
+
+        let migrated: FeeCollector = decode_legacy::<legacy::FeeCollector, _>(&old).unwrap();
+        assert_eq!(migrated.nirv_fee_index, IndexNumber::new(1));
+        assert_eq!(migrated.ana_fee_index, IndexNumber::new(2));
+        assert_eq!(migrated.prana_fee_index, IndexNumber::new(3));
+        assert_eq!(migrated.staged_nirv, NIRV::new(4));
+        assert_eq!(migrated.bump, 252);
+    }
+
+    #[test]
+    fn widens_config() {
+        let old = legacy_bytes::<NirvCenterConfigV3, _>(&legacy::NirvCenterConfigV3 {
+            nirv_fee_index: PreciseNumber::new(1),
+            ana_fee_index: PreciseNumber::new(2),
+            prana_fee_index: PreciseNumber::new(3),
+            reward_index: PreciseNumber::new(18_000),
+            price_curve: Pubkey::new_unique(),
+            bump: 253,
+            ..Default::default()
+        });
+
+        let migrated: NirvCenterConfigV3 =
+            decode_legacy::<legacy::NirvCenterConfigV3, _>(&old).unwrap();
+        let new = encode(&migrated).unwrap();
+
+        let migrated = NirvCenterConfigV3::try_deserialize(&mut new.as_slice()).unwrap();
+        assert_eq!(migrated.nirv_fee_index, IndexNumber::new(1));
+        assert_eq!(migrated.ana_fee_index, IndexNumber::new(2));
+        assert_eq!(migrated.prana_fee_index, IndexNumber::new(3));
+        assert_eq!(migrated.reward_index, IndexNumber::new(18_000));
+        assert_eq!(migrated.total_lock_boost, ANA::ZERO);
+        assert_eq!(migrated.bump, 253);
+    }
+
+    /// Fields only grew after the bump, so the old bytes lead the new ones
+    fn assert_appended<T: AccountSerialize>(old: &[u8], migrated: &T) {
+        let new = encode(migrated).unwrap();
+        assert!(new.len() > old.len());
+        assert_eq!(&new[..old.len()], old);
+    }
+
+    #[test]
+    fn grows_money_market() {
+        let old = legacy_bytes::<MoneyMarket, _>(&legacy::MoneyMarket {
+            for_trana: true,
+            enabled: true,
+            mint: Pubkey::new_unique(),
+            decimals: 6,
+            bump: 249,
+            ..Default::default()
+        });
+
+        let migrated: MoneyMarket = decode_legacy::<legacy::MoneyMarket, _>(&old).unwrap();
+        assert!(migrated.enabled);
+        assert!(!migrated.for_psm);
+        assert_eq!(migrated.bump, 249);
+        assert_appended(&old, &migrated);
+    }
+
+    #[test]
+    fn grows_global_history() {
+        let old = legacy_bytes::<GlobalHistory, _>(&legacy::GlobalHistory {
+            volume_usd: 1_000,
+            nirv_repaid: NIRV::new(5),
+            bump: 248,
+            ..Default::default()
+        });
+
+        let migrated: GlobalHistory = decode_legacy::<legacy::GlobalHistory, _>(&old).unwrap();
+        assert_eq!(migrated.volume_usd, 1_000);
+        assert_eq!(migrated.keeper_bounty_count, 0);
+        assert_eq!(migrated.bump, 248);
+        assert_appended(&old, &migrated);
+    }
+
+    #[test]
+    fn grows_trana_meta() {
+        let old = legacy_bytes::<TranaMeta, _>(&legacy::TranaMeta {
+            enabled: true,
+            ana_outstanding: ANA::new(40),
+            vesting_length_seconds: 1_000,
+            ..Default::default()
+        });
+
+        let migrated: TranaMeta = decode_legacy::<legacy::TranaMeta, _>(&old).unwrap();
+        assert_eq!(migrated.ana_outstanding, ANA::new(40));
+        assert_eq!(migrated.vesting_length_seconds, 1_000);
+        assert_eq!(migrated.capacity, ANA::ZERO);
+        assert_eq!(migrated.debt, ANA::ZERO);
+        assert_appended(&old, &migrated);
+    }
+
+    #[test]
+    fn grows_user_trana_contract() {
+        let old = legacy_bytes::<UserTranaContract, _>(&legacy::UserTranaContract {
+            user: Pubkey::new_unique(),
+            available: true,
+            amount_ana: ANA::new(10),
+            end_time: 2_000,
+            ..Default::default()
+        });
+
+        let migrated: UserTranaContract =
+            decode_legacy::<legacy::UserTranaContract, _>(&old).unwrap();
+        assert!(migrated.available);
+        assert_eq!(migrated.amount_ana, ANA::new(10));
+        assert_eq!(migrated.mint, Pubkey::default());
+        assert!(!migrated.auto_stake);
+        assert_appended(&old, &migrated);
+    }
+
+    #[test]
+    fn user_reward_keeps_its_fields_in_place() {
+        // only the index widened, everything up to the bump stays in order
+        let migrated = UserRewardV2 {
+            bump: 254,
+            ..Default::default()
+        };
+        let new = encode(&migrated).unwrap();
+        assert_eq!(new[8 + 16 + 32 + 4 * 8], 254);
+    }
+
+    #[test]
+    fn only_accounts_with_legacy_layouts() {
+        assert!(is_migratable(&UserRewardV2::discriminator()));
+        assert!(is_migratable(&NirvCenterConfigV3::discriminator()));
+        assert!(!is_migratable(&[0; 8]));
+    }
+}
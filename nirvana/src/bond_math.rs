@@ -6,31 +6,46 @@ use crate::{
 };
 use rust_decimal::prelude::*;
 
-pub fn bond_discount(bond: &TranaMeta, ana_price: &Decimal, floor: &Decimal) -> Decimal {
+/// The bond's discount, the debt should be decayed up to now first
+pub fn bond_discount(
+    bond: &TranaMeta,
+    ana_price: &Decimal,
+    floor: &Decimal,
+    ana_supply: ANA,
+) -> Decimal {
     let bond_max_discount_ratio = bond.max_discount_ratio;
     let max_discount_ratio = compress_max_discount(ana_price, floor, bond_max_discount_ratio);
 
-    bond_discount_ratio(bond.sensitivity, bond.ana_outstanding, max_discount_ratio)
+    bond_discount_ratio(bond.sensitivity, bond.debt, ana_supply, max_discount_ratio)
 }
 
 fn bond_discount_ratio(
     bond_sensitivity: PreciseNumber,
-    ana_outstanding: ANA,
+    debt: ANA,
+    ana_supply: ANA,
     max_discount_ratio: PreciseNumber,
 ) -> Decimal {
     let max_discount_ratio = max_discount_ratio.to_decimal();
-    // the demand is the outstanding ANA * sensitivity
-    let demand = ana_outstanding
+
+    if ana_supply == ANA::ZERO {
+        return max_discount_ratio;
+    }
+
+    // the demand is the debt as a share of supply * sensitivity
+    let demand = debt
         .to_decimal()
+        .checked_div(ana_supply.into())
+        .unwrap()
         .checked_mul(bond_sensitivity.into())
         .unwrap()
         .round_dp_with_strategy(12, RoundingStrategy::AwayFromZero);
 
     // the discount is the max discount - demand
     // ie, more demand is tantamount to less discount
-    let discount = max_discount_ratio.checked_sub(demand).unwrap();
-
-    discount
+    max_discount_ratio
+        .checked_sub(demand)
+        .unwrap()
+        .max(Decimal::ZERO)
 }
 
 /// Checks the discount of the floor relative to ANA,
@@ -94,4 +109,55 @@ mod tests {
         let max_discount = compress_max_discount(&ana, &floor, max_discount_ratio);
         assert_eq!(max_discount.val, 250_000_000_000);
     }
+
+    #[test]
+    fn test_bond_discount_ratio() {
+        // 50% max discount, sensitivity of 10
+        let max_discount_ratio = PreciseNumber::from_decimal(Decimal::new(5, 1));
+        let sensitivity = PreciseNumber::from_decimal(Decimal::new(10, 0));
+        let supply = ANA::new(1_000);
+
+        let discount = bond_discount_ratio(sensitivity, ANA::ZERO, supply, max_discount_ratio);
+        assert_eq!(discount, Decimal::new(5, 1));
+
+        // 1% of supply in debt takes 10% off the discount
+        let discount = bond_discount_ratio(sensitivity, ANA::new(10), supply, max_discount_ratio);
+        assert_eq!(discount, Decimal::new(4, 1));
+
+        // never below no discount
+        let discount = bond_discount_ratio(sensitivity, ANA::new(100), supply, max_discount_ratio);
+        assert_eq!(discount, Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_bond_discount_decays_with_time() {
+        let mut bond = TranaMeta {
+            max_discount_ratio: PreciseNumber::from_decimal(Decimal::new(5, 1)),
+            sensitivity: PreciseNumber::from_decimal(Decimal::new(10, 0)),
+            vesting_length_seconds: 100,
+            ..Default::default()
+        };
+        bond.add_debt(ANA::new(40));
+        let ana = Decimal::new(10, 0);
+        let floor = Decimal::new(1, 0);
+        let supply = ANA::new(1_000);
+
+        assert_eq!(
+            bond_discount(&bond, &ana, &floor, supply),
+            Decimal::new(1, 1)
+        );
+
+        bond.decay_debt(50);
+        assert_eq!(
+            bond_discount(&bond, &ana, &floor, supply),
+            Decimal::new(3, 1)
+        );
+
+        // a full vesting length clears the debt
+        bond.decay_debt(100);
+        assert_eq!(
+            bond_discount(&bond, &ana, &floor, supply),
+            Decimal::new(5, 1)
+        );
+    }
 }
@@ -118,4 +118,186 @@ pub enum ErrorCode {
 
     #[msg("Invalid NIRV borrow utilization")]
     InvalidBorrowUtilization,
+
+    #[msg("Invalid NIRV interest rate model")]
+    InvalidNirvRateModel,
+
+    #[msg("Account has no older layout to migrate from")]
+    AccountNotMigratable,
+
+    #[msg("Account is already migrated")]
+    AccountAlreadyMigrated,
+
+    #[msg("Accounts passed with the migrated account don't belong to it")]
+    InvalidMigrationAccounts,
+
+    #[msg("NIRV position is not undercollateralized")]
+    NirvPositionNotLiquidatable,
+
+    #[msg("NIRV liquidation amount is zero")]
+    NirvLiquidationAmountZero,
+
+    #[msg("Invalid NIRV liquidation parameters")]
+    InvalidNirvLiquidationParams,
+
+    #[msg("Target leverage must be above 1x and within the max NIRV loan ratio")]
+    InvalidTargetLeverage,
+
+    #[msg("Position is already at the target leverage")]
+    LeverageTargetReached,
+
+    #[msg("Self-repaying is not enabled for this NIRV loan")]
+    SelfRepayingNotEnabled,
+
+    #[msg("prANA rewards are not worth more than the floor")]
+    SelfRepayNotProfitable,
+
+    #[msg("Money market not usable for the peg stability module")]
+    MoneyMarketNotForPsm,
+
+    #[msg("Peg stability module debt ceiling exceeded")]
+    PsmDebtCeilingExceeded,
+
+    #[msg("Not enough reserves in the peg stability module")]
+    PsmInsufficientReserves,
+
+    #[msg("PSM fees must be at most 100%")]
+    InvalidPsmFees,
+
+    #[msg("Insufficient NIRV in user account to realize preANA")]
+    InsufficientNirvToRealizePreAna,
+
+    #[msg("Signer is not allowed to compound for this account")]
+    UnauthorizedCompound,
+
+    #[msg("Insufficient unlocked staked ANA")]
+    InsufficientUnlockedANA,
+
+    #[msg("Lock duration is not one of the lock tiers")]
+    InvalidLockDuration,
+
+    #[msg("Lock can't end before the current lock")]
+    LockCannotBeShortened,
+
+    #[msg("Locked ANA can't be unlocked yet")]
+    LockNotExpired,
+
+    #[msg("No ANA is pending unstake")]
+    NoPendingUnstake,
+
+    #[msg("Unstake cooldown is not over")]
+    UnstakeCooldownNotOver,
+
+    #[msg("Unstake cooldown has not been set")]
+    UnstakeCooldownNotSet,
+
+    #[msg("Unstake cooldown is shorter than the minimum")]
+    UnstakeCooldownTooShort,
+
+    #[msg("Amount is too small to convert between ANA and sANA")]
+    SanaAmountTooSmall,
+
+    #[msg("Insufficient sANA to withdraw")]
+    InsufficientSana,
+
+    #[msg("Selling the vault's prANA doesn't cover realizing it")]
+    SanaCompoundNotProfitable,
+
+    #[msg("Signer is not the owner or their delegate")]
+    UnauthorizedDelegate,
+
+    #[msg("Cannot stake into the sANA vault's position")]
+    CannotStakeForSanaVault,
+
+    #[msg("Emission schedule has no room for more epochs")]
+    EmissionScheduleFull,
+
+    #[msg("Emission epochs must start in the future, after the last epoch")]
+    InvalidEmissionEpochStart,
+
+    #[msg("Emission decay must be less than 1")]
+    InvalidEmissionDecay,
+
+    #[msg("Every reward stream the user joined has to be settled")]
+    MissingRewardStreams,
+
+    #[msg("Stream checkpoint does not match the reward stream or user")]
+    InvalidStreamCheckpoint,
+
+    #[msg("Reward stream has ended")]
+    RewardStreamEnded,
+
+    #[msg("Reward stream must end after it starts")]
+    InvalidRewardStreamWindow,
+
+    #[msg("Reward stream is still running or has unclaimed rewards")]
+    RewardStreamNotSettled,
+
+    #[msg("Reward stream has not ended yet")]
+    RewardStreamNotEnded,
+
+    #[msg("Commitment must be closed before the history")]
+    HistoryHasOpenCommitment,
+
+    #[msg("Commitment reward has not been claimed")]
+    CommitmentNotFinished,
+
+    #[msg("trANA contract has not been fully redeemed")]
+    TranaContractNotFinished,
+
+    #[msg("Reward position still has stake, debt, rewards or streams")]
+    UserRewardNotEmpty,
+
+    #[msg("Fee collector still has staked ALMS or fees to claim")]
+    FeeCollectorNotEmpty,
+
+    #[msg("trANA is not enabled")]
+    TranaNotEnabled,
+
+    #[msg("Money market not for trANA")]
+    MoneyMarketNotForTrana,
+
+    #[msg("trANA sale has not opened")]
+    TranaSaleNotOpen,
+
+    #[msg("trANA sale has closed")]
+    TranaSaleClosed,
+
+    #[msg("trANA purchase is over the per-purchase limit")]
+    TranaPurchaseTooLarge,
+
+    #[msg("trANA purchase is over the per-user limit")]
+    TranaUserLimitReached,
+
+    #[msg("trANA bond capacity reached")]
+    TranaCapacityReached,
+
+    #[msg("trANA sale must close after it opens")]
+    InvalidTranaWindow,
+
+    #[msg("Remaining accounts do not match the trANA bonds")]
+    InvalidTranaBatch,
+
+    #[msg("trANA bond stakes on redemption, use redeem_trana_and_stake")]
+    TranaBondAutoStakes,
+
+    #[msg(
+        "trANA cliff and release step must fit the vesting length, and the penalty be at most 100%"
+    )]
+    InvalidTranaVesting,
+
+    #[msg("trANA bond does not allow an early exit")]
+    TranaEarlyExitDisabled,
+
+    #[msg("No borrowed NIRV to repay")]
+    NoNirvDebtToRepay,
+
+    #[msg("Repay the borrowed NIRV before requesting an unstake")]
+    NirvDebtBlocksUnstakeRequest,
+
+    #[msg("Realizing prANA with NIRV is paused")]
+    RealizeWithNirvPaused,
+
+    #[msg("Index is past what a Decimal can hold")]
+    IndexOutOfDecimalRange,
 }
@@ -0,0 +1,44 @@
+use crate::numbers::PreciseNumber;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AddEmissionEpoch<'info> {
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"emission_schedule".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = emission_schedule.bump,
+    )]
+    pub emission_schedule: Box<Account<'info, EmissionSchedule>>,
+
+    pub signer: Signer<'info>,
+}
+
+/// Publish the next epoch of the emission schedule
+///
+/// It has to start in the future and after the last published epoch.
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<AddEmissionEpoch>,
+    start_time: u64,
+    rate: PreciseNumber,
+    decay: PreciseNumber,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    ctx.accounts.emission_schedule.add_epoch(
+        now,
+        EmissionEpoch {
+            start_time,
+            rate,
+            decay,
+        },
+    )
+}
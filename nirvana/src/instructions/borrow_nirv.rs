@@ -1,4 +1,4 @@
-use crate::numbers::{ALMS, NIRV};
+use crate::numbers::{ALMS, ANA, NIRV};
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
@@ -66,6 +66,11 @@ pub struct BorrowNirv<'info> {
     )]
     pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
     #[account(
         mut,
         constraint = nirv_fee_account.key() == config.nirv_fee_account
@@ -76,24 +81,38 @@ pub struct BorrowNirv<'info> {
 }
 pub fn handler(ctx: Context<BorrowNirv>, amount: u64) -> Result<()> {
     let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // bring the user's debt up to date before borrowing more
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
 
     let requested_nirv = NIRV::from_u64(amount);
 
     let (amount_less_fee, fee) = ctx
         .accounts
         .config
-        .collect_nirv_origination_fee(requested_nirv, total_alms_staked);
+        .collect_nirv_origination_fee(requested_nirv, total_alms_staked)?;
 
     ctx.accounts
         .user_reward_index
         .borrow_nirv(requested_nirv, &ctx.accounts.price_field)?;
+    ctx.accounts.config.add_borrowed_nirv(requested_nirv);
 
     // Transfer NIRV to fee account
     token::mint_to(
         ctx.accounts
             .mint_to_fee_context()
             .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
-        fee.into(),
+        fee.val.checked_add(interest.val).unwrap(),
     )?;
 
     // Transfer NIRV to user
@@ -0,0 +1,295 @@
+use crate::{
+    errors::ErrorCode,
+    numbers::{PreciseNumber, ALMS, ANA},
+    price_math::{calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use rust_decimal::prelude::*;
+
+#[derive(Accounts)]
+pub struct BuyAndStake<'info> {
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == signer.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: User must sign to move funds
+    #[account(
+        mut,
+        constraint = user_money_token_account.mint == money_market.mint
+    )]
+    pub user_money_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token account for treasury money
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == money_market.token_account,
+        constraint = treasury_token_account.mint == money_market.mint,
+    )]
+    pub treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            treasury_token_account.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_calculator.bump
+    )]
+    pub price_calculator: Box<Account<'info, PriceFieldV2>>,
+
+    /// CHECK: Key is matched to NirvCenter
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = history.authority == signer.key(),
+        constraint = history.nirv_center == nirv_center.key(),
+        seeds = [
+            b"history".as_ref(),
+            nirv_center.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump = history.bump,
+    )]
+    pub history: Box<Account<'info, History>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms,
+        constraint = stake_pool_alms.mint == config.mint_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Buy ANA and stake it without it passing through the user's wallet
+pub fn handler(
+    ctx: Context<BuyAndStake>,
+    amount_of_ana: ANA,
+    expected_total_cost: u64,
+) -> Result<()> {
+    ctx.accounts.can_swap()?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+
+    let (ana_less_fees, fees) =
+        ctx.accounts
+            .config
+            .collect_ana_swap_fee(true, amount_of_ana, total_alms_staked)?;
+
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    let is_bootstrapping = ctx.accounts.config.is_bootstrapping(now);
+
+    let total_cost_d = ctx.accounts.total_cost(now, amount_of_ana);
+    msg!("Total cost: {}", total_cost_d);
+
+    // update history
+    let round_dollars = total_cost_d
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        .to_u64()
+        .unwrap();
+
+    ctx.accounts
+        .update_history(round_dollars, is_bootstrapping, amount_of_ana);
+
+    // Convert to integer
+    let total_cost = total_cost_d.mantissa().to_u64().unwrap();
+
+    if expected_total_cost < total_cost {
+        return Err(ErrorCode::SlippageExceededForBuy.into());
+    }
+
+    // Update the new price of ANA
+    // NOTE: This does not include the bootstrapping offset
+    let new_ana_supply = ANA::from_u64(ctx.accounts.mint_ana.supply) + amount_of_ana;
+    let price_for_unit = ctx
+        .accounts
+        .price_calculator
+        .price_for_supply(new_ana_supply);
+    ctx.accounts.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+
+    // update how much the user has staked
+    ctx.accounts.user_reward_index.stake_ana(
+        ana_less_fees,
+        &ctx.accounts.config,
+        &ctx.accounts.price_calculator,
+    )?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    // transfer payment
+    token::transfer(ctx.accounts.pay_context(), total_cost)?;
+
+    // stake the ANA
+    token::mint_to(
+        ctx.accounts
+            .mint_to_stake_pool_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_less_fees.into(),
+    )?;
+
+    // collect fee
+    token::mint_to(
+        ctx.accounts
+            .mint_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        fees.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> BuyAndStake<'info> {
+    fn can_swap(&self) -> Result<()> {
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        Ok(())
+    }
+
+    fn update_history(&mut self, round_dollars: u64, is_bootstrapping: bool, amount_of_ana: ANA) {
+        self.global_history.buy_ana(
+            round_dollars,
+            amount_of_ana,
+            ANA::from_u64(self.mint_ana.supply),
+        );
+
+        if is_bootstrapping {
+            self.history
+                .buy_during_bootstrap(round_dollars, amount_of_ana);
+        } else {
+            self.history.buy(round_dollars);
+        }
+    }
+
+    fn total_cost(&self, now: u64, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Buying, so round price up
+        let mut total_cost_d = calc_total_cost_for_amount(
+            ANA::from_u64(self.mint_ana.supply),
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_calculator.to_owned().into_inner(),
+            true,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::AwayFromZero);
+
+        // Scale the precision of the underyling token
+        total_cost_d.rescale(money_scale.into());
+
+        total_cost_d
+    }
+
+    fn pay_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_money_token_account.to_account_info(),
+                to: self.treasury_token_account.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_to_stake_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                to: self.stake_pool_ana.to_account_info(),
+                mint: self.mint_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                to: self.ana_fee_account.to_account_info(),
+                mint: self.mint_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,70 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CancelUnstake<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+}
+
+/// Stake the pending ANA again
+///
+/// The owner's delegate can restake it too.
+pub fn handler(ctx: Context<CancelUnstake>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    if !ctx
+        .accounts
+        .user_reward_index
+        .is_owner_or_delegate(ctx.accounts.authority.key())
+    {
+        return Err(ErrorCode::UnauthorizedDelegate.into());
+    }
+
+    ctx.accounts
+        .user_reward_index
+        .cancel_unstake(&mut ctx.accounts.config, &ctx.accounts.price_field)?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    Ok(())
+}
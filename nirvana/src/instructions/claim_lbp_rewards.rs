@@ -169,7 +169,7 @@ pub fn handler(ctx: Context<ClaimLbpRewards>) -> Result<()> {
 
     // the commitment is over
     ctx.accounts.commitment.claimed_and_dead = true;
-    // There is also case of closing account and getting rent back
+    // the rent can now be reclaimed with close_commitment
 
     Ok(())
 }
@@ -1,3 +1,4 @@
+use crate::errors::ErrorCode;
 use crate::numbers::ALMS;
 use crate::state::*;
 use anchor_lang::prelude::*;
@@ -43,18 +44,17 @@ pub struct ClaimReward<'info> {
 
     #[account(
         mut,
-        constraint = user_pre_ana_account.owner == authority.key()
+        constraint = user_pre_ana_account.owner == user_reward.owner
     )]
     pub user_pre_ana_account: Account<'info, TokenAccount>,
 
     #[account(
         mut,
-        constraint = user_reward.owner == authority.key(),
         constraint = user_reward.to_account_info().owner == program_id, // This check actually breaks composability // NB do you need it ? This is redundant imo
         seeds = [
             b"userreward_v2".as_ref(),
             nirv_center.key().as_ref(),
-            authority.key().as_ref()
+            user_reward.owner.as_ref()
         ],
         bump = user_reward.bump
     )]
@@ -85,14 +85,24 @@ pub struct ClaimReward<'info> {
 }
 
 /// Claim the rewarded prana
+///
+/// The owner's delegate can claim too, but the prANA always goes to the owner.
 pub fn handler(ctx: Context<ClaimReward>) -> Result<()> {
+    if !ctx
+        .accounts
+        .user_reward
+        .is_owner_or_delegate(ctx.accounts.authority.key())
+    {
+        return Err(ErrorCode::UnauthorizedDelegate.into());
+    }
+
     let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
 
     let (reward_less_fee, fee) = ctx.accounts.user_reward.claim_prana_rewards(
         &mut ctx.accounts.config,
         &ctx.accounts.price_field,
         total_alms_staked,
-    );
+    )?;
 
     token::mint_to(
         ctx.accounts
@@ -0,0 +1,115 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ClaimStreamRewards<'info> {
+    /// The owner or their delegate
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"reward_stream".as_ref(),
+            nirv_center.key().as_ref(),
+            reward_stream.reward_mint.as_ref()
+        ],
+        bump = reward_stream.bump,
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    #[account(
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        has_one = reward_stream,
+        constraint = stream_checkpoint.owner == user_reward_index.owner,
+        seeds = [
+            b"stream_checkpoint".as_ref(),
+            reward_stream.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = stream_checkpoint.bump,
+    )]
+    pub stream_checkpoint: Box<Account<'info, StreamCheckpoint>>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == reward_stream.vault
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == reward_stream.reward_mint,
+        constraint = user_token_account.owner == user_reward_index.owner
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claim the tokens earned from a reward stream
+///
+/// Anything the vault can't cover yet stays accrued until the stream is funded.
+pub fn handler(ctx: Context<ClaimStreamRewards>) -> Result<()> {
+    if !ctx
+        .accounts
+        .user_reward_index
+        .is_owner_or_delegate(ctx.accounts.authority.key())
+    {
+        return Err(ErrorCode::UnauthorizedDelegate.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    ctx.accounts.reward_stream.update_index(now)?;
+    ctx.accounts
+        .stream_checkpoint
+        .settle(&ctx.accounts.reward_stream)?;
+
+    let amount = ctx
+        .accounts
+        .stream_checkpoint
+        .take_accrued(ctx.accounts.vault.amount);
+    ctx.accounts.reward_stream.claim(amount);
+
+    token::transfer(
+        ctx.accounts
+            .transfer_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> ClaimStreamRewards<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.user_token_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseCommitment<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = commitment.owner == authority.key(),
+        seeds = [
+            b"commitment".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = commitment.bump,
+    )]
+    pub commitment: Account<'info, Commitment>,
+}
+
+/// Close a claimed commitment and refund the rent
+pub fn handler(ctx: Context<CloseCommitment>) -> Result<()> {
+    if !ctx.accounts.commitment.can_close() {
+        return Err(ErrorCode::CommitmentNotFinished.into());
+    }
+
+    Ok(())
+}
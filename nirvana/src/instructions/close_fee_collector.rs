@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseFeeCollector<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = fee_collector.owner == authority.key(),
+        seeds = [
+            b"feecollector".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = fee_collector.bump,
+    )]
+    pub fee_collector: Account<'info, FeeCollector>,
+}
+
+/// Close an empty fee collector and refund the rent
+pub fn handler(ctx: Context<CloseFeeCollector>) -> Result<()> {
+    if !ctx.accounts.fee_collector.is_empty() {
+        return Err(ErrorCode::FeeCollectorNotEmpty.into());
+    }
+
+    Ok(())
+}
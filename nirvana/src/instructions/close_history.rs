@@ -0,0 +1,46 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseHistory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = nirv_center,
+        has_one = authority,
+        seeds = [
+            b"history".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = history.bump,
+    )]
+    pub history: Account<'info, History>,
+
+    /// The user's commitment, which needs the history to claim its reward
+    /// CHECK - Only checked to be closed
+    #[account(
+        seeds = [
+            b"commitment".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+    )]
+    pub commitment: AccountInfo<'info>,
+}
+
+/// Close the history and refund the rent, once the user has no commitment open
+pub fn handler(ctx: Context<CloseHistory>) -> Result<()> {
+    if !ctx.accounts.commitment.data_is_empty() {
+        return Err(ErrorCode::HistoryHasOpenCommitment.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseUserReward<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+}
+
+/// Close an empty reward position and refund the rent
+///
+/// Every reward stream has to be left first.
+pub fn handler(ctx: Context<CloseUserReward>) -> Result<()> {
+    if !ctx.accounts.user_reward_index.is_empty() {
+        return Err(ErrorCode::UserRewardNotEmpty.into());
+    }
+
+    Ok(())
+}
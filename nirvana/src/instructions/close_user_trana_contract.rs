@@ -0,0 +1,25 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct CloseUserTranaContract<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = user_trana.user == authority.key()
+    )]
+    pub user_trana: Account<'info, UserTranaContract>,
+}
+
+/// Close a fully redeemed trANA contract and refund the rent
+pub fn handler(ctx: Context<CloseUserTranaContract>) -> Result<()> {
+    if !ctx.accounts.user_trana.can_close() {
+        return Err(ErrorCode::TranaContractNotFinished.into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct CompleteUnstake<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = user_token_ana.mint == config.mint_ana,
+        constraint = user_token_ana.owner == authority.key()
+    )]
+    pub user_token_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw the pending ANA, without the unstake fee, once the cooldown is over
+pub fn handler(ctx: Context<CompleteUnstake>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    let amount = ctx
+        .accounts
+        .user_reward_index
+        .complete_unstake(now, &mut ctx.accounts.config)?;
+
+    // Transfer ANA to user
+    token::transfer(
+        ctx.accounts
+            .transfer_user_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        amount.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> CompleteUnstake<'info> {
+    fn transfer_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.user_token_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
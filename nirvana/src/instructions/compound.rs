@@ -0,0 +1,234 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{Decimalable, ALMS};
+use crate::price_math::PriceCalculator;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use rust_decimal::prelude::*;
+
+#[derive(Accounts)]
+pub struct Compound<'info> {
+    /// The owner, their delegate, or the keeper they opted in
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_pre_ana.key() == config.mint_pre_ana
+    )]
+    pub mint_pre_ana: Box<Account<'info, Mint>>,
+
+    /// Pays the floor price, a keeper or delegate must be its SPL delegate
+    #[account(
+        mut,
+        constraint = user_u.owner == user_reward_index.owner,
+        constraint = user_u.mint == money_market.mint,
+    )]
+    pub user_u: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_u.key() == money_market.token_account,
+        constraint = treasury_u.mint == money_market.mint,
+    )]
+    pub treasury_u: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            treasury_u.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump,
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = prana_fee_account.key() == config.prana_fee_account
+    )]
+    pub prana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claim the prANA rewards, realize them at the floor and stake the ANA
+pub fn handler(ctx: Context<Compound>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    ctx.accounts.can_compound()?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+
+    let (reward_less_fee, fee) = ctx.accounts.user_reward_index.claim_prana_rewards(
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+        total_alms_staked,
+    )?;
+
+    // Round up
+    let money_scale: u32 = ctx.accounts.money_market.decimals.into();
+    let mut payment_amount = reward_less_fee
+        .to_decimal()
+        .checked_mul(ctx.accounts.price_field.floor_price.into())
+        .unwrap()
+        .round_dp_with_strategy(money_scale, RoundingStrategy::AwayFromZero);
+    payment_amount.rescale(money_scale);
+    let payment_amount = payment_amount.mantissa().to_u64().unwrap();
+
+    // update the price calculator
+    ctx.accounts
+        .price_field
+        .increase_supply_with_no_price_impact(reward_less_fee);
+    ctx.accounts.global_history.prana_executed += reward_less_fee;
+
+    // update how much the user has staked
+    ctx.accounts.user_reward_index.stake_ana(
+        reward_less_fee,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    // Collect the prANA fee
+    token::mint_to(
+        ctx.accounts
+            .mint_prana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        fee.into(),
+    )?;
+
+    // Transfer the payment
+    token::transfer(ctx.accounts.transfer_context(), payment_amount)?;
+
+    // Mint new ANA to the stake pool
+    token::mint_to(
+        ctx.accounts
+            .mint_to_stake_pool_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        reward_less_fee.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> Compound<'info> {
+    fn can_compound(&self) -> Result<()> {
+        if !self.user_reward_index.can_compound(self.signer.key()) {
+            return Err(ErrorCode::UnauthorizedCompound.into());
+        }
+
+        if !self.money_market.for_prana {
+            return Err(ErrorCode::TokenAccountNotForPrana.into());
+        }
+
+        Ok(())
+    }
+
+    fn mint_prana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_pre_ana.to_account_info(),
+                to: self.prana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_u.to_account_info(),
+                to: self.treasury_u.to_account_info(),
+                authority: self.signer.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_to_stake_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
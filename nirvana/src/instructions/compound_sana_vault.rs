@@ -0,0 +1,385 @@
+use crate::{
+    errors::ErrorCode,
+    numbers::{Decimalable, PreciseNumber, ALMS, ANA, NIRV},
+    price_math::{calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use rust_decimal::prelude::*;
+
+/// Most rounds spent sizing the ANA sold to pay for realizing
+const MAX_SELL_SIZING_STEPS: u8 = 4;
+
+#[event]
+struct CompoundSanaVaultEvent {
+    prana_realized: u64,
+    ana_sold: u64,
+    ana_staked: u64,
+    vault_staked_amount: u64,
+}
+
+#[derive(Accounts)]
+pub struct CompoundSanaVault<'info> {
+    /// Anyone can crank the vault
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"sana_vault".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = sana_vault.bump,
+    )]
+    pub sana_vault: Box<Account<'info, SanaVault>>,
+
+    #[account(
+        mut,
+        constraint = vault_reward.owner == sana_vault.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            sana_vault.key().as_ref()
+        ],
+        bump = vault_reward.bump
+    )]
+    pub vault_reward: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_pre_ana.key() == config.mint_pre_ana
+    )]
+    pub mint_pre_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            mint_nirv.key().as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = prana_fee_account.key() == config.prana_fee_account
+    )]
+    pub prana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Compound the vault's prANA back into its stake
+///
+/// The prANA is realized at the floor and just enough of the ANA is sold
+/// to pay for it, so the NIRV from the sale covers the realize cost and
+/// no tokens leave the treasury. The rest is staked, raising the sANA exchange rate.
+pub fn handler(ctx: Context<CompoundSanaVault>) -> Result<()> {
+    ctx.accounts.can_compound()?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    let (prana_rewards, prana_fee) = ctx.accounts.vault_reward.claim_prana_rewards(
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+        total_alms_staked,
+    )?;
+
+    // Realize the prANA at the floor, rounding up
+    let realize_cost = prana_rewards
+        .to_decimal()
+        .checked_mul(ctx.accounts.price_field.floor_price.into())
+        .unwrap()
+        .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+    let realize_cost = NIRV::from_decimal(realize_cost);
+
+    ctx.accounts
+        .price_field
+        .increase_supply_with_no_price_impact(prana_rewards);
+    let realized_supply = ANA::from_u64(ctx.accounts.mint_ana.supply) + prana_rewards;
+
+    let ana_to_sell = ctx
+        .accounts
+        .size_sale(now, realized_supply, prana_rewards, realize_cost)?;
+
+    let (ana_sold, sell_fee) =
+        ctx.accounts
+            .config
+            .collect_ana_swap_fee(false, ana_to_sell, total_alms_staked)?;
+    let total_proceeds_d = ctx.accounts.total_proceeds(now, realized_supply, ana_sold);
+    let ana_staked = prana_rewards - ana_to_sell;
+
+    ctx.accounts.vault_reward.stake_ana(
+        ana_staked,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+
+    ctx.accounts
+        .update_history(total_proceeds_d, prana_rewards, realized_supply, ana_sold);
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.vault_reward,
+        now,
+    )?;
+
+    // Collect the prANA fee
+    token::mint_to(
+        ctx.accounts
+            .mint_prana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        prana_fee.into(),
+    )?;
+
+    // Mint the ANA kept into the stake pool
+    token::mint_to(
+        ctx.accounts
+            .mint_ana_to_stake_pool_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_staked.into(),
+    )?;
+
+    // Collect the sell fee
+    token::mint_to(
+        ctx.accounts
+            .mint_ana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        sell_fee.into(),
+    )?;
+
+    emit!(CompoundSanaVaultEvent {
+        prana_realized: prana_rewards.into(),
+        ana_sold: ana_sold.into(),
+        ana_staked: ana_staked.into(),
+        vault_staked_amount: ctx.accounts.vault_reward.staked_amount.into(),
+    });
+
+    Ok(())
+}
+
+impl<'info> CompoundSanaVault<'info> {
+    fn can_compound(&self) -> Result<()> {
+        if !self.money_market.for_prana {
+            return Err(ErrorCode::TokenAccountNotForPrana.into());
+        }
+
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        Ok(())
+    }
+
+    /// ANA left to sell once the sell fee is taken
+    fn less_sell_fee(&self, amount: ANA) -> ANA {
+        let fee = amount
+            .to_decimal()
+            .checked_mul(self.config.sell_fee.to_decimal())
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+        ANA::from_decimal(amount.to_decimal().checked_sub(fee).unwrap())
+    }
+
+    fn proceeds_for(&self, now: u64, supply: ANA, amount: ANA) -> u64 {
+        self.total_proceeds(now, supply, self.less_sell_fee(amount))
+            .mantissa()
+            .to_u64()
+            .unwrap()
+    }
+
+    /// Smallest sale, fee included, that pays for the realized prANA
+    ///
+    /// Selling less moves the price less, so scaling the sale by
+    /// cost / proceeds always still covers the cost and each step shrinks it.
+    fn size_sale(&self, now: u64, supply: ANA, prana_rewards: ANA, cost: NIRV) -> Result<ANA> {
+        let mut amount = prana_rewards;
+        let mut proceeds = self.proceeds_for(now, supply, amount);
+
+        if proceeds < cost.val {
+            return Err(ErrorCode::SanaCompoundNotProfitable.into());
+        }
+
+        for _ in 0..MAX_SELL_SIZING_STEPS {
+            if proceeds == cost.val {
+                break;
+            }
+
+            let next = amount
+                .to_decimal()
+                .checked_mul(cost.to_decimal())
+                .unwrap()
+                .checked_div(NIRV::from_u64(proceeds).into())
+                .unwrap()
+                .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::AwayFromZero);
+            let next = ANA::from_decimal(next);
+            let next_proceeds = self.proceeds_for(now, supply, next);
+
+            // Rounding can leave a sale just short
+            if next_proceeds < cost.val || next.val >= amount.val {
+                break;
+            }
+
+            amount = next;
+            proceeds = next_proceeds;
+        }
+
+        Ok(amount)
+    }
+
+    fn total_proceeds(&self, now: u64, supply: ANA, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Selling, so round price down
+        let mut total_proceeds_d = calc_total_cost_for_amount(
+            supply,
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_field.to_owned().into_inner(),
+            false,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::ToZero);
+
+        // Scale the precision of the underyling token
+        total_proceeds_d.rescale(money_scale.into());
+
+        total_proceeds_d
+    }
+
+    fn update_history(
+        &mut self,
+        total_proceeds_d: Decimal,
+        prana_realized: ANA,
+        realized_supply: ANA,
+        ana_sold: ANA,
+    ) {
+        let round_dollars = total_proceeds_d
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_u64()
+            .unwrap();
+        let new_ana_supply = realized_supply - ana_sold;
+
+        self.global_history.prana_executed += prana_realized;
+        self.global_history.sell_ana(round_dollars, ana_sold);
+
+        // Update the new price of ANA
+        // NOTE: This does not include the bootstrapping offset
+        let price_for_unit = self.price_field.price_for_supply(new_ana_supply);
+        self.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+
+        self.price_field
+            .reset_slippage_start_point_if_needed(new_ana_supply);
+    }
+
+    fn mint_prana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_pre_ana.to_account_info(),
+                to: self.prana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_ana_to_stake_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_ana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,75 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CreateRewardStream<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    pub reward_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        token::mint = reward_mint,
+        token::authority = nirv_center_authority,
+        payer = signer
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"reward_stream".as_ref(),
+            nirv_center.key().as_ref(),
+            reward_mint.key().as_ref()
+        ],
+        bump,
+        payer = signer
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+/// Create a stream paying a token to ANA stakers
+///
+/// The stream pays out no more than what has been funded into its vault.
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<CreateRewardStream>,
+    bump: u8,
+    emission_per_second: u64,
+    start_time: u64,
+    end_time: u64,
+) -> Result<()> {
+    if end_time <= start_time {
+        return Err(ErrorCode::InvalidRewardStreamWindow.into());
+    }
+
+    let reward_stream = &mut ctx.accounts.reward_stream;
+
+    reward_stream.nirv_center = ctx.accounts.nirv_center.key();
+    reward_stream.reward_mint = ctx.accounts.reward_mint.key();
+    reward_stream.decimals = ctx.accounts.reward_mint.decimals;
+    reward_stream.vault = ctx.accounts.vault.key();
+    reward_stream.emission_per_second = emission_per_second;
+    reward_stream.start_time = start_time;
+    reward_stream.end_time = end_time;
+    reward_stream.last_update_time = start_time;
+    reward_stream.bump = bump;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use crate::numbers::{ArbitraryNumber, PreciseNumber, ANA};
 use crate::state::*;
-use crate::utils::{admin, is_debug};
+use crate::utils::admin;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token};
 
@@ -41,7 +41,6 @@ pub struct CreateTranaMeta<'info> {
     pub token_program: Program<'info, Token>,
 }
 #[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.authority))]
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(
     ctx: Context<CreateTranaMeta>,
     vesting_length_seconds: u64,
@@ -62,8 +61,8 @@ pub fn handler(
     trana_account.ana_outstanding = ANA::ZERO;
     trana_account.total_bought = ArbitraryNumber { val: 0, scale };
     trana_account.money_market = money_market.key();
-    // TODO - do not enable by default
-    trana_account.enabled = true;
+    // enabled by the admin once it is ready to sell
+    trana_account.enabled = false;
 
     Ok(())
 }
@@ -0,0 +1,154 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ANA;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct DepositSana<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        has_one = nirv_center,
+        has_one = mint_sana,
+        seeds = [
+            b"sana_vault".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = sana_vault.bump,
+    )]
+    pub sana_vault: Box<Account<'info, SanaVault>>,
+
+    #[account(mut)]
+    pub mint_sana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = vault_reward.owner == sana_vault.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            sana_vault.key().as_ref()
+        ],
+        bump = vault_reward.bump
+    )]
+    pub vault_reward: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = user_token_ana.mint == config.mint_ana,
+        constraint = user_token_ana.owner == authority.key()
+    )]
+    pub user_token_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_sana.mint == mint_sana.key()
+    )]
+    pub user_sana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Stake ANA through the vault and receive sANA
+///
+/// sANA is minted at the current exchange rate.
+/// prANA the vault hasn't compounded yet is not counted, so cranking
+/// compound_sana_vault first gives existing holders their full share.
+pub fn handler(ctx: Context<DepositSana>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    let amount = ANA::from_u64(amount);
+
+    let vault_assets = SanaVault::deposit_assets(
+        &ctx.accounts.vault_reward,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+    let sana_amount =
+        SanaVault::sana_for_deposit(amount, vault_assets, ctx.accounts.mint_sana.supply);
+
+    if sana_amount == 0 {
+        return Err(ErrorCode::SanaAmountTooSmall.into());
+    }
+
+    ctx.accounts
+        .vault_reward
+        .stake_ana(amount, &ctx.accounts.config, &ctx.accounts.price_field)?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.vault_reward,
+        now,
+    )?;
+
+    token::transfer(ctx.accounts.transfer_ana_context(), amount.into())?;
+
+    token::mint_to(
+        ctx.accounts
+            .mint_sana_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        sana_amount,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> DepositSana<'info> {
+    fn transfer_ana_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_token_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_sana_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_sana.to_account_info(),
+                to: self.user_sana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,182 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ALMS;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Key;
+use anchor_spl::token;
+use anchor_spl::token::Burn;
+use anchor_spl::token::Mint;
+use anchor_spl::token::Token;
+use anchor_spl::token::TokenAccount;
+use anchor_spl::token::Transfer;
+
+#[derive(Accounts)]
+pub struct ExitTranaEarly<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        constraint = mint_ana.key() == config.mint_ana
+    )]
+    pub mint_ana: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_trana.trana_meta == trana_meta.key()
+    )]
+    pub user_trana: Box<Account<'info, UserTranaContract>>,
+
+    #[account(
+        mut,
+        constraint = trana_mint.key() == user_trana.mint
+    )]
+    pub trana_mint: Box<Account<'info, Mint>>,
+
+    /// Holds the bond token, whoever owns it owns the bond
+    #[account(
+        mut,
+        constraint = trana_token.mint == trana_mint.key(),
+        constraint = trana_token.owner == authority.key(),
+        constraint = trana_token.amount == 1 @ErrorCode::UnauthorizedBondRedemption
+    )]
+    pub trana_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key(),
+        seeds = [
+            b"trana_v1".as_ref(),
+            trana_meta.vesting_length_seconds.to_string().as_bytes(),
+            money_market.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
+    )]
+    pub trana_meta: Box<Account<'info, TranaMeta>>,
+
+    #[account(
+        constraint = money_market.key() == trana_meta.money_market
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = user_ana.mint == mint_ana.key(),
+        constraint = user_ana.owner == authority.key()
+    )]
+    pub user_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_ana.key() == config.treasury_ana
+    )]
+    pub treasury_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+/// Redeem everything left in a trANA bond now, the unvested ANA less the early exit penalty
+///
+/// The penalty goes to ALMS stakers as an ANA fee.
+pub fn handler(ctx: Context<ExitTranaEarly>) -> Result<()> {
+    ctx.accounts.can_exit_early()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let (payout, penalty) = ctx.accounts.user_trana.exit_early(now);
+
+    // The bond is done with, so is its token
+    token::burn(ctx.accounts.burn_trana_token_context(), 1)?;
+
+    // Decrease outstanding ANA
+    ctx.accounts
+        .trana_meta
+        .sub_ana_outstanding(payout + penalty);
+
+    // Collect the penalty
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    ctx.accounts
+        .config
+        .collect_ana_fee(penalty, total_alms_staked)?;
+
+    token::transfer(
+        ctx.accounts
+            .transfer_context(ctx.accounts.user_ana.to_account_info())
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        payout.into(),
+    )?;
+
+    token::transfer(
+        ctx.accounts
+            .transfer_context(ctx.accounts.ana_fee_account.to_account_info())
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        penalty.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> ExitTranaEarly<'info> {
+    fn can_exit_early(&self) -> Result<()> {
+        // Bond is in initial state
+        if self.user_trana.available {
+            return Err(ErrorCode::RedeemUnusedBond.into());
+        }
+
+        if !self.user_trana.early_exit_enabled {
+            return Err(ErrorCode::TranaEarlyExitDisabled.into());
+        }
+        Ok(())
+    }
+
+    fn burn_trana_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.trana_mint.to_account_info(),
+                to: self.trana_token.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_context(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_ana.to_account_info(),
+                to,
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,63 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Account<'info, UserRewardV2>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Account<'info, PriceFieldV2>,
+}
+
+/// Relock the locked ANA for one of the lock tiers, starting now
+pub fn handler(ctx: Context<ExtendLock>, duration_seconds: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    ctx.accounts.user_reward_index.extend_lock(
+        duration_seconds,
+        now,
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    Ok(())
+}
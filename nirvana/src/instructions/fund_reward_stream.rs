@@ -0,0 +1,56 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct FundRewardStream<'info> {
+    /// Anyone can fund a stream
+    pub funder: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == reward_stream.reward_mint
+    )]
+    pub funder_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"reward_stream".as_ref(),
+            reward_stream.nirv_center.as_ref(),
+            reward_stream.reward_mint.as_ref()
+        ],
+        bump = reward_stream.bump,
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == reward_stream.vault
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<FundRewardStream>, amount: u64) -> Result<()> {
+    let reward_stream = &mut ctx.accounts.reward_stream;
+    reward_stream.total_funded = reward_stream.total_funded.checked_add(amount).unwrap();
+
+    token::transfer(ctx.accounts.transfer_context(), amount)?;
+
+    Ok(())
+}
+
+impl<'info> FundRewardStream<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.funder_token_account.to_account_info(),
+                to: self.vault.to_account_info(),
+                authority: self.funder.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,80 @@
+use crate::numbers::{ANA, NIRV};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[event]
+struct NirvHealthEvent {
+    owner: Pubkey,
+    borrowed_nirv: u64,
+    borrow_limit: u64,
+    liquidatable: bool,
+}
+
+#[derive(Accounts)]
+pub struct GetNirvHealth<'info> {
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+}
+
+/// Report the health of a NIRV loan with interest accrued until now
+pub fn handler(ctx: Context<GetNirvHealth>) -> Result<()> {
+    let mut config = ctx.accounts.config.clone().into_inner();
+    let mut user_reward = ctx.accounts.user_reward_index.clone().into_inner();
+    let price_field = &ctx.accounts.price_field;
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // nothing is written back, the accrual only prices the debt
+    config.accrue_nirv_interest(now, total_ana_staked, price_field.floor_price);
+    let interest = user_reward.calc_nirv_interest(config.nirv_borrow_index);
+    user_reward.borrowed_nirv += interest;
+
+    let borrow_limit = NIRV::from_decimal(user_reward.get_nirv_borrow_limit(price_field));
+    msg!(
+        "Health factor: {}",
+        user_reward.get_nirv_health_factor(price_field)
+    );
+
+    emit!(NirvHealthEvent {
+        owner: user_reward.owner,
+        borrowed_nirv: user_reward.borrowed_nirv.into(),
+        borrow_limit: borrow_limit.into(),
+        liquidatable: user_reward.is_nirv_liquidatable(price_field),
+    });
+
+    Ok(())
+}
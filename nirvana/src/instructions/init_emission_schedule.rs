@@ -0,0 +1,34 @@
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitEmissionSchedule<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"emission_schedule".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
+        payer = signer
+    )]
+    pub emission_schedule: Box<Account<'info, EmissionSchedule>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(ctx: Context<InitEmissionSchedule>, bump: u8) -> Result<()> {
+    let emission_schedule = &mut ctx.accounts.emission_schedule;
+
+    emission_schedule.nirv_center = ctx.accounts.nirv_center.key();
+    emission_schedule.bump = bump;
+
+    Ok(())
+}
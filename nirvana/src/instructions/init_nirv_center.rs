@@ -1,5 +1,5 @@
 use crate::{
-    numbers::{CoarseNumber, PreciseNumber},
+    numbers::{CoarseNumber, IndexNumber, PreciseNumber},
     state::*,
 };
 use anchor_lang::prelude::*;
@@ -84,10 +84,13 @@ pub fn handler(ctx: Context<InitNirvCenter>, config: NirvCenterInitConfig) -> Re
     ctx.accounts.price_curve.bump = *price_curve_bump;
 
     c.prana_reward_rate = PreciseNumber { val: 1_000_000_000 };
-    c.reward_index = PreciseNumber::ZERO;
+    c.reward_index = IndexNumber::ZERO;
+
+    c.nirv_borrow_index = PreciseNumber::new(1);
 
     let now = Clock::get()?.unix_timestamp.unsigned_abs();
     c.time_of_last_prana_reward = now;
+    c.time_of_last_nirv_accrual = now;
     c.bump = *config_bump;
 
     Ok(())
@@ -1,6 +1,6 @@
 use crate::utils::admin;
 use crate::{
-    numbers::{CoarseNumber, PreciseNumber},
+    numbers::{CoarseNumber, IndexNumber, PreciseNumber},
     state::*,
 };
 use anchor_lang::prelude::*;
@@ -56,9 +56,11 @@ pub fn handler(ctx: Context<InitNirvCenterConfig>, config: InitNirvCenterConfigA
     c.prana_reward_interval_seconds = config.prana_reward_interval_seconds;
 
     c.prana_reward_rate = PreciseNumber { val: 1_000_000_000 };
-    c.reward_index = PreciseNumber::ZERO;
+    c.reward_index = IndexNumber::ZERO;
+    c.nirv_borrow_index = PreciseNumber::new(1);
 
     c.time_of_last_prana_reward = now;
+    c.time_of_last_nirv_accrual = now;
     c.bump = *config_bump;
 
     Ok(())
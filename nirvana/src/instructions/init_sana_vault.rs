@@ -0,0 +1,86 @@
+use crate::numbers::ANA;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token};
+
+#[derive(Accounts)]
+pub struct InitSanaVault<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"sana_vault".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
+        payer = signer
+    )]
+    pub sana_vault: Box<Account<'info, SanaVault>>,
+
+    #[account(
+        init,
+        mint::decimals = 6,
+        mint::authority = nirv_center_authority,
+        payer = signer
+    )]
+    pub mint_sana: Box<Account<'info, Mint>>,
+
+    /// The vault's staking position
+    #[account(
+        init,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            sana_vault.key().as_ref()
+        ],
+        bump,
+        payer = signer
+    )]
+    pub vault_reward: Box<Account<'info, UserRewardV2>>,
+
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(ctx: Context<InitSanaVault>, bump: u8, reward_bump: u8) -> Result<()> {
+    let sana_vault = &mut ctx.accounts.sana_vault;
+
+    sana_vault.nirv_center = ctx.accounts.nirv_center.key();
+    sana_vault.mint_sana = ctx.accounts.mint_sana.key();
+    sana_vault.bump = bump;
+
+    // The vault owns its position, so no one can sign to borrow NIRV against it
+    let vault_reward = &mut ctx.accounts.vault_reward;
+
+    vault_reward.owner = sana_vault.key();
+    vault_reward.index = ctx.accounts.config.reward_index;
+    vault_reward.staked_amount = ANA::ZERO;
+    vault_reward.staged_pre_ana_rewards = ANA::ZERO;
+    vault_reward.staged_pre_ana_fees = ANA::ZERO;
+    vault_reward.nirv_borrow_index = ctx.accounts.config.nirv_borrow_index;
+    vault_reward.bump = reward_bump;
+
+    Ok(())
+}
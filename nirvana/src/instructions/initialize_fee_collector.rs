@@ -1,4 +1,4 @@
-use crate::numbers::{PreciseNumber, ALMS, ANA, NIRV};
+use crate::numbers::{IndexNumber, ALMS, ANA, NIRV};
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
@@ -28,9 +28,9 @@ pub fn handler(ctx: Context<InitializeFeeCollector>, bump: u8) -> Result<()> {
     let fee_collector = &mut ctx.accounts.fee_collector;
 
     fee_collector.owner = ctx.accounts.authority.key();
-    fee_collector.nirv_fee_index = PreciseNumber::ZERO;
-    fee_collector.ana_fee_index = PreciseNumber::ZERO;
-    fee_collector.prana_fee_index = PreciseNumber::ZERO;
+    fee_collector.nirv_fee_index = IndexNumber::ZERO;
+    fee_collector.ana_fee_index = IndexNumber::ZERO;
+    fee_collector.prana_fee_index = IndexNumber::ZERO;
     fee_collector.staked_alms = ALMS::ZERO;
     fee_collector.staged_ana = ANA::ZERO;
     fee_collector.staged_nirv = NIRV::ZERO;
@@ -41,6 +41,7 @@ pub fn handler(ctx: Context<InitializeUserReward>, bump: u8) -> Result<()> {
     user_reward.staked_amount = ANA::ZERO;
     user_reward.staged_pre_ana_rewards = ANA::ZERO;
     user_reward.staged_pre_ana_fees = ANA::ZERO;
+    user_reward.nirv_borrow_index = ctx.accounts.config.nirv_borrow_index;
     user_reward.bump = bump;
 
     Ok(())
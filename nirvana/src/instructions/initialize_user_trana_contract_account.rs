@@ -1,5 +1,4 @@
 use crate::state::*;
-use crate::utils::is_debug;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
 
@@ -26,7 +25,6 @@ pub struct InitializeUserTranaContractAccount<'info> {
     pub system_program: Program<'info, System>,
     pub nirv_center: Account<'info, NirvCenter>,
 }
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(
     ctx: Context<InitializeUserTranaContractAccount>,
     _bump: u8,
@@ -0,0 +1,79 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct JoinRewardStream<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"reward_stream".as_ref(),
+            nirv_center.key().as_ref(),
+            reward_stream.reward_mint.as_ref()
+        ],
+        bump = reward_stream.bump,
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        init,
+        seeds = [
+            b"stream_checkpoint".as_ref(),
+            reward_stream.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+        payer = authority
+    )]
+    pub stream_checkpoint: Box<Account<'info, StreamCheckpoint>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Start earning from a reward stream
+///
+/// Every change to the stake or the lock settles the streams joined, so all of them are passed to it.
+pub fn handler(ctx: Context<JoinRewardStream>, bump: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    if !ctx.accounts.reward_stream.is_active(now) {
+        return Err(ErrorCode::RewardStreamEnded.into());
+    }
+
+    let reward_stream = &mut ctx.accounts.reward_stream;
+    reward_stream.update_index(now)?;
+
+    let stream_checkpoint = &mut ctx.accounts.stream_checkpoint;
+
+    stream_checkpoint.reward_stream = reward_stream.key();
+    stream_checkpoint.owner = ctx.accounts.authority.key();
+    stream_checkpoint.bump = bump;
+
+    // Starts from the current index, with nothing earned before joining
+    reward_stream.reweight(
+        stream_checkpoint,
+        ctx.accounts.user_reward_index.effective_staked_amount(),
+    )?;
+
+    ctx.accounts.user_reward_index.stream_count += 1;
+
+    Ok(())
+}
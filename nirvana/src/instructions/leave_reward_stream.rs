@@ -0,0 +1,73 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ANA;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct LeaveRewardStream<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"reward_stream".as_ref(),
+            nirv_center.key().as_ref(),
+            reward_stream.reward_mint.as_ref()
+        ],
+        bump = reward_stream.bump,
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = reward_stream,
+        constraint = stream_checkpoint.owner == authority.key(),
+        seeds = [
+            b"stream_checkpoint".as_ref(),
+            reward_stream.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = stream_checkpoint.bump,
+    )]
+    pub stream_checkpoint: Box<Account<'info, StreamCheckpoint>>,
+}
+
+/// Close the checkpoint of an ended stream once everything is claimed
+///
+/// The stream no longer has to be passed when staking or unstaking.
+pub fn handler(ctx: Context<LeaveRewardStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    let reward_stream = &mut ctx.accounts.reward_stream;
+    let stream_checkpoint = &mut ctx.accounts.stream_checkpoint;
+
+    // Everything was emitted and the checkpoint caught up with it
+    let settled = reward_stream.last_update_time >= reward_stream.end_time
+        && stream_checkpoint.index.val == reward_stream.index.val
+        && stream_checkpoint.accrued == 0;
+
+    if reward_stream.is_active(now) || !settled {
+        return Err(ErrorCode::RewardStreamNotSettled.into());
+    }
+
+    reward_stream.reweight(stream_checkpoint, ANA::ZERO)?;
+    ctx.accounts.user_reward_index.stream_count -= 1;
+
+    Ok(())
+}
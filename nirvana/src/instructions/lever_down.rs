@@ -0,0 +1,372 @@
+use crate::{
+    errors::ErrorCode,
+    numbers::{PreciseNumber, ALMS, ANA, NIRV},
+    price_math::{calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use rust_decimal::prelude::*;
+use std::cmp;
+
+#[derive(Accounts)]
+pub struct LeverDown<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_nirv.mint == mint_nirv.key()
+    )]
+    pub user_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    /// Token account for treasury NIRV
+    #[account(
+        mut,
+        constraint = treasury_nirv.key() == money_market.token_account,
+        constraint = treasury_nirv.mint == mint_nirv.key(),
+    )]
+    pub treasury_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            mint_nirv.key().as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = history.authority == authority.key(),
+        constraint = history.nirv_center == nirv_center.key(),
+        seeds = [
+            b"history".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = history.bump,
+    )]
+    pub history: Box<Account<'info, History>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Unstake ANA, sell it for NIRV and repay the NIRV loan
+///
+/// Proceeds beyond the debt are sent to the user.
+/// min_total_proceeds bounds the NIRV received, like the expected cost of a swap.
+pub fn handler(ctx: Context<LeverDown>, amount: u64, min_total_proceeds: u64) -> Result<()> {
+    ctx.accounts.can_lever_down(amount)?;
+
+    let amount = ANA::from_u64(amount);
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // the debt is repaid including interest
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
+    // Unstake and sell fees together, as unstake_and_sell charges them
+    let (ana_sold, fees) = ctx
+        .accounts
+        .config
+        .collect_ana_unstake_and_sell_fee(amount, total_alms_staked)?;
+
+    let total_proceeds_d = ctx.accounts.total_proceeds(now, ana_sold);
+    let total_proceeds = total_proceeds_d.mantissa().to_u64().unwrap();
+    msg!("ANA sold: {}", ana_sold.val);
+    msg!("Total proceeds: {}", total_proceeds);
+
+    if min_total_proceeds > total_proceeds {
+        return Err(ErrorCode::SlippageExceededForSell.into());
+    }
+
+    // Repay as much of the loan as the proceeds cover
+    let repay = NIRV::from_u64(cmp::min(
+        total_proceeds,
+        ctx.accounts.user_reward_index.borrowed_nirv.val,
+    ));
+    let excess = total_proceeds.checked_sub(repay.val).unwrap();
+
+    ctx.accounts.user_reward_index.deleverage(
+        amount,
+        repay,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+    ctx.accounts.config.sub_borrowed_nirv(repay);
+
+    let is_bootstrapping = ctx.accounts.config.is_bootstrapping(now);
+    ctx.accounts
+        .update_history(total_proceeds_d, is_bootstrapping, ana_sold);
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    // Burn the ANA sold
+    token::burn(
+        ctx.accounts
+            .burn_ana_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_sold.into(),
+    )?;
+
+    // Transfer ANA fees to fee account
+    token::transfer(
+        ctx.accounts
+            .transfer_ana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        fees.into(),
+    )?;
+
+    // Burn the NIRV repaid out of the sale proceeds
+    token::burn(
+        ctx.accounts
+            .burn_nirv_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        repay.into(),
+    )?;
+
+    // Transfer the rest of the proceeds to the user
+    token::transfer(
+        ctx.accounts
+            .transfer_nirv_to_user_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        excess,
+    )?;
+
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_nirv_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> LeverDown<'info> {
+    fn can_lever_down(&self, amount: u64) -> Result<()> {
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        if amount > self.user_reward_index.staked_amount.val {
+            return Err(ErrorCode::InsufficientStakedANAToUnstake.into());
+        }
+
+        Ok(())
+    }
+
+    fn total_proceeds(&self, now: u64, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Selling, so round price down
+        let mut total_proceeds_d = calc_total_cost_for_amount(
+            ANA::from_u64(self.mint_ana.supply),
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_field.to_owned().into_inner(),
+            false,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::ToZero);
+
+        // Scale the precision of the underyling token
+        total_proceeds_d.rescale(money_scale.into());
+
+        total_proceeds_d
+    }
+
+    fn update_history(
+        &mut self,
+        total_proceeds_d: Decimal,
+        is_bootstrapping: bool,
+        amount_of_ana: ANA,
+    ) {
+        let round_dollars = total_proceeds_d
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_u64()
+            .unwrap();
+        let new_ana_supply = ANA::from_u64(self.mint_ana.supply) - amount_of_ana;
+
+        self.global_history.sell_ana(round_dollars, amount_of_ana);
+
+        if is_bootstrapping {
+            self.history
+                .sell_during_bootstrap(round_dollars, amount_of_ana);
+        } else {
+            self.history.sell(round_dollars);
+        }
+
+        // Update the new price of ANA
+        // NOTE: This does not include the bootstrapping offset
+        let price_for_unit = self.price_field.price_for_supply(new_ana_supply);
+        self.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+
+        self.price_field
+            .reset_slippage_start_point_if_needed(new_ana_supply);
+    }
+
+    fn burn_ana_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_ana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn burn_nirv_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.treasury_nirv.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_nirv_to_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_nirv.to_account_info(),
+                to: self.user_nirv.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_nirv_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,464 @@
+use crate::{
+    errors::ErrorCode,
+    leverage_math::{
+        ana_to_reach_leverage, effective_price, leverage, max_leverage, MAX_LEVER_STEPS,
+    },
+    numbers::{CoarseNumber, Decimalable, PreciseNumber, ALMS, ANA, NIRV},
+    price_math::{calc_price, calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use rust_decimal::prelude::*;
+
+#[derive(Accounts)]
+pub struct LeverUp<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    /// Token account for treasury NIRV
+    #[account(
+        mut,
+        constraint = treasury_nirv.key() == money_market.token_account,
+        constraint = treasury_nirv.mint == mint_nirv.key(),
+    )]
+    pub treasury_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            mint_nirv.key().as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = history.authority == authority.key(),
+        constraint = history.nirv_center == nirv_center.key(),
+        seeds = [
+            b"history".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = history.bump,
+    )]
+    pub history: Box<Account<'info, History>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Borrow NIRV, buy ANA with it and stake the ANA, until the target leverage is reached
+///
+/// max_total_cost bounds the NIRV spent on ANA, like the expected cost of a swap.
+pub fn handler(
+    ctx: Context<LeverUp>,
+    target_leverage: CoarseNumber,
+    max_total_cost: u64,
+) -> Result<()> {
+    ctx.accounts.can_lever(target_leverage)?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // bring the user's debt up to date before borrowing more
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
+    let (amount_of_ana, total_cost_d) = ctx.accounts.size_position(now, target_leverage);
+
+    if amount_of_ana == ANA::ZERO {
+        return Err(ErrorCode::LeverageTargetReached.into());
+    }
+
+    let total_cost = total_cost_d.mantissa().to_u64().unwrap();
+    msg!("ANA: {}", amount_of_ana.val);
+    msg!("Total cost: {}", total_cost);
+
+    if max_total_cost < total_cost {
+        return Err(ErrorCode::SlippageExceededForBuy.into());
+    }
+
+    // Borrow enough that the loan less the origination fee pays for the ANA
+    let requested_nirv = total_cost_d
+        .checked_div(
+            Decimal::ONE
+                .checked_sub(ctx.accounts.config.nirv_loan_origination_fee.into())
+                .unwrap(),
+        )
+        .unwrap()
+        .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+    let requested_nirv = NIRV::from_decimal(requested_nirv);
+
+    let (nirv_less_fee, nirv_fee) = ctx
+        .accounts
+        .config
+        .collect_nirv_origination_fee(requested_nirv, total_alms_staked)?;
+    let (ana_less_fee, ana_fee) =
+        ctx.accounts
+            .config
+            .collect_ana_swap_fee(true, amount_of_ana, total_alms_staked)?;
+
+    // stake the ANA, then borrow against it
+    ctx.accounts.user_reward_index.stake_ana(
+        ana_less_fee,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+    ctx.accounts
+        .user_reward_index
+        .borrow_nirv(requested_nirv, &ctx.accounts.price_field)?;
+    ctx.accounts.config.add_borrowed_nirv(requested_nirv);
+
+    let max_leverage = max_leverage(ctx.accounts.config.max_nirv_loan_ratio.into());
+    let new_leverage = leverage(
+        ctx.accounts.user_reward_index.staked_amount,
+        ctx.accounts.user_reward_index.borrowed_nirv,
+        ctx.accounts.price_field.floor_price.into(),
+    );
+    if new_leverage > max_leverage {
+        return Err(ErrorCode::InvalidTargetLeverage.into());
+    }
+
+    let is_bootstrapping = ctx.accounts.config.is_bootstrapping(now);
+    ctx.accounts
+        .update_history(total_cost_d, is_bootstrapping, amount_of_ana);
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    // Pay the treasury with the borrowed NIRV
+    token::mint_to(
+        ctx.accounts
+            .mint_nirv_to_treasury_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        nirv_less_fee.into(),
+    )?;
+
+    // Transfer NIRV fee and interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_nirv_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        nirv_fee.val.checked_add(interest.val).unwrap(),
+    )?;
+
+    // Stake the ANA bought
+    token::mint_to(
+        ctx.accounts
+            .mint_ana_to_stake_pool_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_less_fee.into(),
+    )?;
+
+    // collect fee
+    token::mint_to(
+        ctx.accounts
+            .mint_ana_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_fee.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> LeverUp<'info> {
+    fn can_lever(&self, target_leverage: CoarseNumber) -> Result<()> {
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        let target_leverage = target_leverage.to_decimal();
+        let max_leverage = max_leverage(self.config.max_nirv_loan_ratio.into());
+
+        if target_leverage <= Decimal::ONE || target_leverage > max_leverage {
+            return Err(ErrorCode::InvalidTargetLeverage.into());
+        }
+
+        Ok(())
+    }
+
+    /// How much ANA to buy, and its total cost
+    ///
+    /// Each step sizes the purchase at the current price, then again at the
+    /// average price of that purchase so it never overshoots the target.
+    fn size_position(&self, now: u64, target_leverage: CoarseNumber) -> (ANA, Decimal) {
+        let target_leverage = target_leverage.to_decimal();
+        let floor = self.price_field.floor_price.to_decimal();
+        let buy_fee = self.config.instant_buy_fee.to_decimal();
+        let origination_fee = self.config.nirv_loan_origination_fee.to_decimal();
+        let kept_after_buy_fee = Decimal::ONE.checked_sub(buy_fee).unwrap();
+
+        let mut supply = ANA::from_u64(self.mint_ana.supply);
+        let mut staked = self.user_reward_index.staked_amount;
+        let mut borrowed = self.user_reward_index.borrowed_nirv;
+
+        let mut amount_of_ana = ANA::ZERO;
+        let mut total_cost = Decimal::ZERO;
+
+        for _ in 0..MAX_LEVER_STEPS {
+            let mut price = self.buy_price(now, supply);
+            let mut step = (ANA::ZERO, Decimal::ZERO);
+
+            for _ in 0..2 {
+                let ana_to_stake = ana_to_reach_leverage(
+                    staked,
+                    borrowed,
+                    floor,
+                    effective_price(price, buy_fee, origination_fee),
+                    target_leverage,
+                );
+                let ana_to_buy = ana_to_stake
+                    .to_decimal()
+                    .checked_div(kept_after_buy_fee)
+                    .unwrap()
+                    .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+                let ana_to_buy = ANA::from_decimal(ana_to_buy);
+
+                if ana_to_buy == ANA::ZERO {
+                    step = (ANA::ZERO, Decimal::ZERO);
+                    break;
+                }
+
+                let cost = self.total_cost(now, supply, ana_to_buy);
+                price = cost.checked_div(ana_to_buy.into()).unwrap();
+                step = (ana_to_buy, cost);
+            }
+
+            let (ana_to_buy, cost) = step;
+            if ana_to_buy == ANA::ZERO {
+                break;
+            }
+
+            let step_borrowed = cost
+                .checked_div(Decimal::ONE.checked_sub(origination_fee).unwrap())
+                .unwrap()
+                .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+            let step_staked = ana_to_buy
+                .to_decimal()
+                .checked_mul(kept_after_buy_fee)
+                .unwrap()
+                .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+            supply += ana_to_buy;
+            staked += ANA::from_decimal(step_staked);
+            borrowed += NIRV::from_decimal(step_borrowed);
+            amount_of_ana += ana_to_buy;
+            total_cost = total_cost.checked_add(cost).unwrap();
+        }
+
+        (amount_of_ana, total_cost)
+    }
+
+    /// Price of the next ANA, including the bootstrapping offset
+    fn buy_price(&self, now: u64, supply: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+
+        calc_price(
+            supply,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_field.to_owned().into_inner(),
+            true,
+        )
+        .checked_add(bootstrap_params.current_offset(now))
+        .unwrap()
+    }
+
+    fn total_cost(&self, now: u64, supply: ANA, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Buying, so round price up
+        let mut total_cost_d = calc_total_cost_for_amount(
+            supply,
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_field.to_owned().into_inner(),
+            true,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::AwayFromZero);
+
+        // Scale the precision of the underyling token
+        total_cost_d.rescale(money_scale.into());
+
+        total_cost_d
+    }
+
+    fn update_history(
+        &mut self,
+        total_cost_d: Decimal,
+        is_bootstrapping: bool,
+        amount_of_ana: ANA,
+    ) {
+        let round_dollars = total_cost_d
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_u64()
+            .unwrap();
+        let current_supply = ANA::from_u64(self.mint_ana.supply);
+
+        self.global_history
+            .buy_ana(round_dollars, amount_of_ana, current_supply);
+
+        if is_bootstrapping {
+            self.history
+                .buy_during_bootstrap(round_dollars, amount_of_ana);
+        } else {
+            self.history.buy(round_dollars);
+        }
+
+        // Update the new price of ANA
+        // NOTE: This does not include the bootstrapping offset
+        let price_for_unit = self
+            .price_field
+            .price_for_supply(current_supply + amount_of_ana);
+        self.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+    }
+
+    fn mint_nirv_to_treasury_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.treasury_nirv.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_nirv_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_ana_to_stake_pool_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_ana_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
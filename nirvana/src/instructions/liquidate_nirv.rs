@@ -0,0 +1,219 @@
+use crate::numbers::{ALMS, ANA, NIRV};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+
+#[event]
+struct LiquidateNirvEvent {
+    borrower: Pubkey,
+    liquidator: Pubkey,
+    nirv_repaid: u64,
+    ana_seized: u64,
+    remaining_borrowed_nirv: u64,
+    remaining_staked_ana: u64,
+    bad_debt_written_off: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct LiquidateNirv<'info> {
+    pub liquidator: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            borrower_reward_index.owner.as_ref()
+        ],
+        bump = borrower_reward_index.bump
+    )]
+    pub borrower_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = liquidator_nirv.mint == mint_nirv.key(),
+        constraint = liquidator_nirv.owner == liquidator.key()
+    )]
+    pub liquidator_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = liquidator_ana.mint == config.mint_ana
+    )]
+    pub liquidator_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Repay an undercollateralized borrower's NIRV in exchange for their staked ANA
+pub fn handler(ctx: Context<LiquidateNirv>, amount: u64) -> Result<()> {
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // the borrower's debt includes the interest accrued until now
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .borrower_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
+    // the borrower keeps the rewards earned on the ANA that is seized
+    ctx.accounts
+        .borrower_reward_index
+        .stage_rewards(&ctx.accounts.config, &ctx.accounts.price_field)?;
+
+    let (nirv_repaid, ana_seized, bad_debt) = ctx.accounts.borrower_reward_index.liquidate_nirv(
+        NIRV::from_u64(amount),
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+    ctx.accounts.config.sub_borrowed_nirv(nirv_repaid);
+    ctx.accounts.global_history.nirv_bad_debt += bad_debt;
+
+    // Every reward stream the borrower joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.borrower_reward_index,
+        now,
+    )?;
+
+    // Burn the liquidator's NIRV
+    token::burn(ctx.accounts.burn_context(), nirv_repaid.into())?;
+
+    // Transfer the seized ANA to the liquidator
+    token::transfer(
+        ctx.accounts
+            .transfer_seized_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_seized.into(),
+    )?;
+
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
+
+    let borrower_reward = &ctx.accounts.borrower_reward_index;
+    msg!(
+        "Health factor after liquidation: {}",
+        borrower_reward.get_nirv_health_factor(&ctx.accounts.price_field)
+    );
+
+    emit!(LiquidateNirvEvent {
+        borrower: borrower_reward.owner,
+        liquidator: ctx.accounts.liquidator.key(),
+        nirv_repaid: nirv_repaid.into(),
+        ana_seized: ana_seized.into(),
+        remaining_borrowed_nirv: borrower_reward.borrowed_nirv.into(),
+        remaining_staked_ana: borrower_reward.staked_amount.into(),
+        bad_debt_written_off: bad_debt.into(),
+    });
+
+    Ok(())
+}
+
+impl<'info> LiquidateNirv<'info> {
+    fn burn_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.liquidator_nirv.to_account_info(),
+                authority: self.liquidator.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_seized_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.liquidator_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
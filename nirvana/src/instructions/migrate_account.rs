@@ -0,0 +1,118 @@
+use crate::account_migration::{decode_legacy, encode, is_migratable, legacy};
+use crate::errors::ErrorCode;
+use crate::numbers::PreciseNumber;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use anchor_lang::Discriminator;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// Pays the rent for the bigger account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// An account written before its indices widened or its fields grew
+    /// CHECK - The owner and discriminator are checked in the handler
+    #[account(mut, owner = *program_id)]
+    pub account: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Rewrite an account from its legacy layout, widening the indices and adding the new fields
+///
+/// The values are kept as they were, so anyone can migrate any account.
+/// The config is migrated first, then a UserRewardV2 is passed the NirvCenter
+/// and its config in the remaining accounts, so its borrow counts toward the total.
+pub fn handler(ctx: Context<MigrateAccount>) -> Result<()> {
+    let account = &ctx.accounts.account;
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    let old = account.try_borrow_data()?.to_vec();
+    if old.len() < 8 || !is_migratable(&old[..8]) {
+        return Err(ErrorCode::AccountNotMigratable.into());
+    }
+    let discriminator = &old[..8];
+
+    let new = if discriminator == UserRewardV2::discriminator() {
+        let user_reward: UserRewardV2 = decode_legacy::<legacy::UserRewardV2, _>(&old)?;
+        count_borrow(
+            ctx.remaining_accounts,
+            ctx.program_id,
+            account.key,
+            &user_reward,
+        )?;
+        encode(&user_reward)?
+    } else if discriminator == FeeCollector::discriminator() {
+        encode(&decode_legacy::<legacy::FeeCollector, FeeCollector>(&old)?)?
+    } else if discriminator == NirvCenterConfigV3::discriminator() {
+        let mut config: NirvCenterConfigV3 = decode_legacy::<legacy::NirvCenterConfigV3, _>(&old)?;
+        config.nirv_borrow_index = PreciseNumber::new(1);
+        config.time_of_last_nirv_accrual = now;
+        encode(&config)?
+    } else if discriminator == MoneyMarket::discriminator() {
+        encode(&decode_legacy::<legacy::MoneyMarket, MoneyMarket>(&old)?)?
+    } else if discriminator == GlobalHistory::discriminator() {
+        encode(&decode_legacy::<legacy::GlobalHistory, GlobalHistory>(
+            &old,
+        )?)?
+    } else if discriminator == TranaMeta::discriminator() {
+        encode(&decode_legacy::<legacy::TranaMeta, TranaMeta>(&old)?)?
+    } else {
+        encode(&decode_legacy::<
+            legacy::UserTranaContract,
+            UserTranaContract,
+        >(&old)?)?
+    };
+
+    // Top up the rent for the extra bytes
+    let rent = Rent::get()?.minimum_balance(new.len());
+    let shortfall = rent.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.payer.key, account.key, shortfall),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                account.clone(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    account.realloc(new.len(), false)?;
+    account.try_borrow_mut_data()?.copy_from_slice(&new);
+
+    Ok(())
+}
+
+/// Add a migrated user's borrow to the total in their config
+fn count_borrow<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    program_id: &Pubkey,
+    user_reward_key: &Pubkey,
+    user_reward: &UserRewardV2,
+) -> Result<()> {
+    if remaining_accounts.len() != 2 {
+        return Err(ErrorCode::InvalidMigrationAccounts.into());
+    }
+    let nirv_center = remaining_accounts[0].key;
+    let mut config: Account<'info, NirvCenterConfigV3> = Account::try_from(&remaining_accounts[1])?;
+
+    let (config_key, _) =
+        Pubkey::find_program_address(&[b"config_v3".as_ref(), nirv_center.as_ref()], program_id);
+    let (user_reward_pda, _) = Pubkey::find_program_address(
+        &[
+            b"userreward_v2".as_ref(),
+            nirv_center.as_ref(),
+            user_reward.owner.as_ref(),
+        ],
+        program_id,
+    );
+    if config.key() != config_key || *user_reward_key != user_reward_pda {
+        return Err(ErrorCode::InvalidMigrationAccounts.into());
+    }
+
+    config.add_borrowed_nirv(user_reward.borrowed_nirv);
+    config.exit(program_id)
+}
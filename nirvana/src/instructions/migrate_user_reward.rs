@@ -0,0 +1,96 @@
+use crate::numbers::ALMS;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Key;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct MigrateUserReward<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        close = authority,
+        constraint = user_reward_v1.owner == authority.key(),
+        seeds = [
+            b"userreward".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+    )]
+    pub user_reward_v1: Box<Account<'info, UserReward>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+        payer = authority
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Move a v1 UserReward into the UserRewardV2, creating it if needed
+///
+/// The v1 account is closed and its rent refunded. If the v2 account has joined
+/// reward streams they are passed in the remaining accounts, as for stake_ana.
+pub fn handler(ctx: Context<MigrateUserReward>, bump: u8) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    let config = &mut ctx.accounts.config;
+    let user_reward = &mut ctx.accounts.user_reward_index;
+
+    if user_reward.owner == Pubkey::default() {
+        user_reward.owner = ctx.accounts.authority.key();
+        user_reward.index = config.reward_index;
+        user_reward.nirv_borrow_index = config.nirv_borrow_index;
+        user_reward.bump = bump;
+    } else {
+        // Interest on the v2 borrow is owed up to now, before the v1 borrow joins it
+        user_reward
+            .accrue_nirv_interest(config, ALMS::from_u64(ctx.accounts.stake_pool_alms.amount))?;
+    }
+
+    user_reward.migrate_from_v1(
+        &mut ctx.accounts.user_reward_v1,
+        config,
+        &ctx.accounts.price_field,
+    )?;
+
+    settle_reward_streams(ctx.remaining_accounts, ctx.program_id, user_reward, now)?;
+
+    Ok(())
+}
@@ -1,11 +1,30 @@
+pub mod add_emission_epoch;
 pub mod borrow_nirv;
+pub mod buy_and_stake;
 pub mod buyback_ana;
+pub mod cancel_unstake;
 pub mod claim_lbp_rewards;
 pub mod claim_reward;
+pub mod claim_stream_rewards;
+pub mod close_commitment;
 pub mod close_config_v2;
+pub mod close_fee_collector;
+pub mod close_history;
+pub mod close_user_reward;
+pub mod close_user_trana_contract;
+pub mod complete_unstake;
+pub mod compound;
+pub mod compound_sana_vault;
+pub mod create_reward_stream;
 pub mod create_trana_meta;
+pub mod deposit_sana;
+pub mod exit_trana_early;
+pub mod extend_lock;
+pub mod fund_reward_stream;
+pub mod get_nirv_health;
 pub mod init_commitment;
 pub mod init_commitment_meta;
+pub mod init_emission_schedule;
 pub mod init_fee_config;
 pub mod init_global_history;
 pub mod init_history;
@@ -13,53 +32,107 @@ pub mod init_money_market;
 pub mod init_nirv_center;
 pub mod init_nirv_center_config;
 pub mod init_price_curve_v2;
+pub mod init_sana_vault;
 pub mod initialize_fee_collector;
 pub mod initialize_user_reward;
 pub mod initialize_user_trana_contract_account;
+pub mod join_reward_stream;
+pub mod leave_reward_stream;
+pub mod lever_down;
+pub mod lever_up;
+pub mod liquidate_nirv;
+pub mod lock_ana;
+pub mod migrate_account;
+pub mod migrate_user_reward;
 pub mod mint_alms;
 pub mod mint_nirv;
 pub mod mint_pre_ana;
+pub mod psm_mint;
+pub mod psm_redeem;
 pub mod purchase_trana;
 pub mod realize_pre_ana;
+pub mod realize_pre_ana_with_nirv;
+pub mod reclaim_reward_stream;
+pub mod redeem_all_trana;
 pub mod redeem_trana;
+pub mod redeem_trana_and_stake;
 pub mod repay_nirv;
+pub mod request_unstake;
 pub mod reward;
 pub mod reward_by_time;
+pub mod self_repay_nirv;
 pub mod set_commitment;
 pub mod set_commitment_meta;
+pub mod set_compound_keeper;
 pub mod set_debug_mode;
+pub mod set_delegate;
 pub mod set_fee_config;
 pub mod set_instant_buy_fee;
+pub mod set_keeper_bounty;
 pub mod set_mint_ana;
 pub mod set_mint_pre_ana;
 pub mod set_nirv_debt_fee;
+pub mod set_nirv_interest_rate_model;
+pub mod set_nirv_liquidation_params;
 pub mod set_nirv_loan_origination_fee;
 pub mod set_price_field_params;
+pub mod set_psm_fees;
+pub mod set_psm_market;
+pub mod set_realize_with_nirv_paused;
 pub mod set_reward_rate;
+pub mod set_self_repaying;
 pub mod set_sell_fee;
 pub mod set_trana_buy_fee;
+pub mod set_trana_capacity;
 pub mod set_trana_enabled;
 pub mod set_trana_max_discount;
 pub mod set_trana_sensitivity;
+pub mod set_trana_vesting;
+pub mod set_trana_window;
 pub mod set_treasury_account_for_amm;
 pub mod set_treasury_account_for_prana;
+pub mod set_treasury_account_for_trana;
 pub mod set_treasury_account_rfv;
+pub mod set_unstake_cooldown;
 pub mod set_unstake_fee;
 pub mod stake_alms;
 pub mod stake_ana;
+pub mod stake_ana_for;
 pub mod start_bootstrapping;
 pub mod swap;
+pub mod unlock_ana;
 pub mod unstake_alms;
 pub mod unstake_ana;
+pub mod unstake_and_sell;
+pub mod withdraw_sana;
 
+pub use add_emission_epoch::*;
 pub use borrow_nirv::*;
+pub use buy_and_stake::*;
 pub use buyback_ana::*;
+pub use cancel_unstake::*;
 pub use claim_lbp_rewards::*;
 pub use claim_reward::*;
+pub use claim_stream_rewards::*;
+pub use close_commitment::*;
 pub use close_config_v2::*;
+pub use close_fee_collector::*;
+pub use close_history::*;
+pub use close_user_reward::*;
+pub use close_user_trana_contract::*;
+pub use complete_unstake::*;
+pub use compound::*;
+pub use compound_sana_vault::*;
+pub use create_reward_stream::*;
 pub use create_trana_meta::*;
+pub use deposit_sana::*;
+pub use exit_trana_early::*;
+pub use extend_lock::*;
+pub use fund_reward_stream::*;
+pub use get_nirv_health::*;
 pub use init_commitment::*;
 pub use init_commitment_meta::*;
+pub use init_emission_schedule::*;
 pub use init_fee_config::*;
 pub use init_global_history::*;
 pub use init_history::*;
@@ -67,41 +140,76 @@ pub use init_money_market::*;
 pub use init_nirv_center::*;
 pub use init_nirv_center_config::*;
 pub use init_price_curve_v2::*;
+pub use init_sana_vault::*;
 pub use initialize_fee_collector::*;
 pub use initialize_user_reward::*;
 pub use initialize_user_trana_contract_account::*;
+pub use join_reward_stream::*;
+pub use leave_reward_stream::*;
+pub use lever_down::*;
+pub use lever_up::*;
+pub use liquidate_nirv::*;
+pub use lock_ana::*;
+pub use migrate_account::*;
+pub use migrate_user_reward::*;
 pub use mint_alms::*;
 pub use mint_nirv::*;
 pub use mint_pre_ana::*;
+pub use psm_mint::*;
+pub use psm_redeem::*;
 pub use purchase_trana::*;
 pub use realize_pre_ana::*;
+pub use realize_pre_ana_with_nirv::*;
+pub use reclaim_reward_stream::*;
+pub use redeem_all_trana::*;
 pub use redeem_trana::*;
+pub use redeem_trana_and_stake::*;
 pub use repay_nirv::*;
+pub use request_unstake::*;
 pub use reward::*;
 pub use reward_by_time::*;
+pub use self_repay_nirv::*;
 pub use set_commitment::*;
 pub use set_commitment_meta::*;
+pub use set_compound_keeper::*;
 pub use set_debug_mode::*;
+pub use set_delegate::*;
 pub use set_fee_config::*;
 pub use set_instant_buy_fee::*;
+pub use set_keeper_bounty::*;
 pub use set_mint_ana::*;
 pub use set_mint_pre_ana::*;
 pub use set_nirv_debt_fee::*;
+pub use set_nirv_interest_rate_model::*;
+pub use set_nirv_liquidation_params::*;
 pub use set_nirv_loan_origination_fee::*;
 pub use set_price_field_params::*;
+pub use set_psm_fees::*;
+pub use set_psm_market::*;
+pub use set_realize_with_nirv_paused::*;
 pub use set_reward_rate::*;
+pub use set_self_repaying::*;
 pub use set_sell_fee::*;
 pub use set_trana_buy_fee::*;
+pub use set_trana_capacity::*;
 pub use set_trana_enabled::*;
 pub use set_trana_max_discount::*;
 pub use set_trana_sensitivity::*;
+pub use set_trana_vesting::*;
+pub use set_trana_window::*;
 pub use set_treasury_account_for_amm::*;
 pub use set_treasury_account_for_prana::*;
+pub use set_treasury_account_for_trana::*;
 pub use set_treasury_account_rfv::*;
+pub use set_unstake_cooldown::*;
 pub use set_unstake_fee::*;
 pub use stake_alms::*;
 pub use stake_ana::*;
+pub use stake_ana_for::*;
 pub use start_bootstrapping::*;
 pub use swap::*;
+pub use unlock_ana::*;
 pub use unstake_alms::*;
 pub use unstake_ana::*;
+pub use unstake_and_sell::*;
+pub use withdraw_sana::*;
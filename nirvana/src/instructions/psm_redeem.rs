@@ -0,0 +1,165 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{ALMS, NIRV};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct PsmRedeem<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_nirv.mint == mint_nirv.key()
+    )]
+    pub user_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_money_token_account.mint == money_market.mint
+    )]
+    pub user_money_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token account for treasury money
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == money_market.token_account,
+        constraint = treasury_token_account.mint == money_market.mint,
+    )]
+    pub treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            treasury_token_account.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Burn NIRV for the stablecoin 1:1, less the PSM redeem fee
+pub fn handler(ctx: Context<PsmRedeem>, amount: u64) -> Result<()> {
+    ctx.accounts.can_psm()?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+
+    let (amount_less_fee, fee) =
+        ctx.accounts
+            .config
+            .collect_psm_fee(false, NIRV::from_u64(amount), total_alms_staked)?;
+
+    ctx.accounts.money_market.psm_redeem(amount_less_fee)?;
+    let redeemed = ctx.accounts.money_market.amount_for_nirv(amount_less_fee);
+
+    ctx.accounts.global_history.nirv_repaid += amount_less_fee;
+
+    // Burn the NIRV redeemed
+    token::burn(ctx.accounts.burn_context(), amount_less_fee.into())?;
+
+    // Transfer NIRV fee to fee account
+    token::transfer(ctx.accounts.transfer_fee_context(), fee.into())?;
+
+    // Transfer the stablecoin to the user
+    token::transfer(
+        ctx.accounts
+            .withdraw_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        redeemed,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> PsmRedeem<'info> {
+    fn can_psm(&self) -> Result<()> {
+        if !self.money_market.enabled || !self.money_market.for_psm {
+            return Err(ErrorCode::MoneyMarketNotForPsm.into());
+        }
+
+        Ok(())
+    }
+
+    fn burn_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.user_nirv.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn withdraw_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_token_account.to_account_info(),
+                to: self.user_money_token_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
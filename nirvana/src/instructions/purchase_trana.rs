@@ -5,15 +5,16 @@ use crate::numbers::Decimalable;
 use crate::numbers::{ALMS, ANA};
 use crate::price_math::PriceCalculator;
 use crate::state::*;
-use crate::utils::is_debug;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
+use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::{self, *};
 use rust_decimal::prelude::*;
 use std::convert::TryInto;
 
 #[derive(Accounts)]
 pub struct PurchaseTrana<'info> {
+    #[account(mut)]
     pub authority: Signer<'info>,
 
     pub nirv_center: Box<Account<'info, NirvCenter>>,
@@ -54,7 +55,14 @@ pub struct PurchaseTrana<'info> {
     #[account(
         mut,
         constraint = trana_meta.nirv_center == nirv_center.key(),
-        constraint = trana_meta.money_market == money_market.key()
+        constraint = trana_meta.money_market == money_market.key(),
+        seeds = [
+            b"trana_v1".as_ref(),
+            trana_meta.vesting_length_seconds.to_string().as_bytes(),
+            money_market.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
     )]
     pub trana_meta: Box<Account<'info, TranaMeta>>,
 
@@ -90,6 +98,41 @@ pub struct PurchaseTrana<'info> {
     )]
     pub user_trana: Box<Account<'info, UserTranaContract>>,
 
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"trana_purchases".as_ref(),
+            trana_meta.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump,
+        payer = authority
+    )]
+    pub user_trana_purchases: Box<Account<'info, UserTranaPurchases>>,
+
+    /// Mints the token that carries the bond, reused each time the contract is bought into again
+    #[account(
+        init_if_needed,
+        seeds = [
+            b"trana_mint".as_ref(),
+            user_trana.key().as_ref()
+        ],
+        bump,
+        mint::decimals = 0,
+        mint::authority = nirv_center_authority,
+        payer = authority
+    )]
+    pub trana_mint: Box<Account<'info, Mint>>,
+
+    /// The buyer's associated account for the bond token, created on the first purchase
+    #[account(
+        init_if_needed,
+        associated_token::mint = trana_mint,
+        associated_token::authority = authority,
+        payer = authority
+    )]
+    pub trana_token: Box<Account<'info, TokenAccount>>,
+
     #[account(
         mut,
         constraint = price_field.nirv_center == nirv_center.key(),
@@ -107,13 +150,22 @@ pub struct PurchaseTrana<'info> {
     pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(
     ctx: Context<PurchaseTrana>,
     payment_u64: u64,
     max_offered_price_u64: u64,
+    auto_stake: bool,
 ) -> Result<()> {
+    ctx.accounts.can_purchase()?;
+
+    // Bring the debt up to now before it prices the bond
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.trana_meta.decay_debt(now);
+
     let trana_meta = &ctx.accounts.trana_meta;
     let pf = &ctx.accounts.price_field;
     let money_market = &ctx.accounts.money_market;
@@ -127,7 +179,7 @@ pub fn handler(
     let floor = pf.floor_price.to_decimal();
     let native_base_price = pf.price_for_supply(ana_supply);
 
-    let discount_ratio = bond_discount(&trana_meta, &native_base_price, &floor);
+    let discount_ratio = bond_discount(trana_meta, &native_base_price, &floor, ana_supply);
     let discount_ratio_complement = Decimal::ONE.checked_sub(discount_ratio).unwrap();
 
     // Apply discount
@@ -155,13 +207,23 @@ pub fn handler(
         .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
     let ana_bought = ANA::from_decimal(ana_bought);
 
+    // Enforce the sale window and limits
+    let user_trana_purchases = &mut ctx.accounts.user_trana_purchases;
+    ctx.accounts
+        .trana_meta
+        .sell_ana(ana_bought, user_trana_purchases.ana_bought, now)?;
+    user_trana_purchases.trana_meta = ctx.accounts.trana_meta.key();
+    user_trana_purchases.user = ctx.accounts.authority.key();
+    user_trana_purchases.ana_bought += ana_bought;
+    user_trana_purchases.bump = *ctx.bumps.get("user_trana_purchases").unwrap();
+
     let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
 
     // Calculate ANA fee
     let (ana_bought_less_fee, fee) = ctx
         .accounts
         .config
-        .collect_trana_buy_fee(ana_bought, total_alms_staked);
+        .collect_trana_buy_fee(ana_bought, total_alms_staked)?;
 
     // Transfer payment to to treasury account
     token::transfer(ctx.accounts.transfer_payment_context(), payment_u64)?;
@@ -190,6 +252,7 @@ pub fn handler(
     // increase the outstanding ANA
     let trana_meta = &mut ctx.accounts.trana_meta;
     trana_meta.ana_outstanding += ana_bought_less_fee;
+    trana_meta.add_debt(ana_bought_less_fee);
 
     // increase total bought, for bookkeeping purposes
     trana_meta.total_bought.val = trana_meta
@@ -199,7 +262,6 @@ pub fn handler(
         .unwrap();
 
     // Configure the user's trana contract
-    let now = Clock::get()?.unix_timestamp;
     let price_in_underlying =
         ArbitraryNumber::from_decimal(native_min_price, money_market.decimals.into());
 
@@ -212,11 +274,46 @@ pub fn handler(
     user_trana.trana_meta = trana_meta.key();
     user_trana.end_time = now + trana_meta.vesting_length_seconds.to_i64().unwrap();
     user_trana.user = ctx.accounts.authority.key();
+    user_trana.mint = ctx.accounts.trana_mint.key();
+    user_trana.auto_stake = auto_stake;
+    user_trana.cliff_time = now + trana_meta.cliff_seconds.to_i64().unwrap();
+    user_trana.release_step_seconds = trana_meta.release_step_seconds;
+    user_trana.early_exit_enabled = trana_meta.early_exit_enabled;
+    user_trana.early_exit_penalty = trana_meta.early_exit_penalty;
+
+    // Mint the token that carries the bond
+    token::mint_to(
+        ctx.accounts
+            .mint_trana_token_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        1,
+    )?;
 
     Ok(())
 }
 
 impl<'info> PurchaseTrana<'info> {
+    fn can_purchase(&self) -> Result<()> {
+        // The last holder burns the token on the final redemption
+        if self.trana_mint.supply != 0 {
+            return Err(error!(ErrorCode::UnavailableBondAccount));
+        }
+
+        if !self.trana_meta.enabled {
+            return Err(error!(ErrorCode::TranaNotEnabled));
+        }
+
+        if !self.money_market.enabled {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        if !self.money_market.for_trana {
+            return Err(error!(ErrorCode::MoneyMarketNotForTrana));
+        }
+
+        Ok(())
+    }
+
     fn transfer_payment_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
@@ -239,6 +336,17 @@ impl<'info> PurchaseTrana<'info> {
         )
     }
 
+    fn mint_trana_token_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                to: self.trana_token.to_account_info(),
+                mint: self.trana_mint.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
     fn mint_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
@@ -0,0 +1,184 @@
+use crate::errors::ErrorCode;
+use crate::numbers::Decimalable;
+use crate::numbers::{ANA, NIRV};
+use crate::price_math::PriceCalculator;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Key;
+use anchor_spl::token::{self, *};
+use rust_decimal::prelude::*;
+
+#[derive(Accounts)]
+pub struct RealizePreAnaWithNirv<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        constraint = user_ana.mint == mint_ana.key(),
+        constraint = user_ana.owner == authority.key(),
+    )]
+    pub user_ana: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_pre_ana.mint == mint_pre_ana.key(),
+        constraint = user_pre_ana.owner == authority.key(),
+    )]
+    pub user_pre_ana: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = config.mint_pre_ana == mint_pre_ana.key(),
+    )]
+    pub mint_pre_ana: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = user_nirv.mint == mint_nirv.key(),
+        constraint = user_nirv.owner == authority.key(),
+    )]
+    pub user_nirv: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv,
+    )]
+    pub mint_nirv: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump,
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Realize prANA by burning NIRV at the floor price
+pub fn handler(ctx: Context<RealizePreAnaWithNirv>, pre_ana_amount: u64) -> Result<()> {
+    if ctx.accounts.config.realize_with_nirv_paused {
+        return Err(ErrorCode::RealizeWithNirvPaused.into());
+    }
+
+    let floor = ctx.accounts.price_field.floor_price.to_decimal();
+
+    ctx.accounts.sufficient_prana(pre_ana_amount)?;
+
+    // Round up
+    let payment_amount = ANA::from_u64(pre_ana_amount)
+        .to_decimal()
+        .checked_mul(floor)
+        .unwrap()
+        .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+    let payment_amount = NIRV::from_decimal(payment_amount);
+
+    if payment_amount.val > ctx.accounts.user_nirv.amount {
+        return Err(ErrorCode::InsufficientNirvToRealizePreAna.into());
+    }
+
+    // update the price calculator
+    ctx.accounts
+        .price_field
+        .increase_supply_with_no_price_impact(ANA::from_u64(pre_ana_amount));
+
+    let global_history = &mut ctx.accounts.global_history;
+    global_history.prana_executed += ANA::from_u64(pre_ana_amount);
+    global_history.prana_purchased += ANA::from_u64(pre_ana_amount);
+
+    // Burn the prANA
+    token::burn(ctx.accounts.burn_prana_context(), pre_ana_amount)?;
+
+    // Burn the NIRV payment
+    token::burn(ctx.accounts.burn_nirv_context(), payment_amount.into())?;
+
+    // Mint new ANA to the user
+    token::mint_to(
+        ctx.accounts
+            .mint_to_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        pre_ana_amount,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> RealizePreAnaWithNirv<'info> {
+    fn sufficient_prana(&self, amount: u64) -> Result<()> {
+        let pre_ana_balance = self.user_pre_ana.amount;
+        if amount > pre_ana_balance {
+            return Err(ErrorCode::InsufficientPreAnaToRealize.into());
+        }
+        Ok(())
+    }
+
+    fn mint_to_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.user_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn burn_nirv_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.user_nirv.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn burn_prana_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_pre_ana.to_account_info(),
+                to: self.user_pre_ana.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,83 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct ReclaimRewardStream<'info> {
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"reward_stream".as_ref(),
+            nirv_center.key().as_ref(),
+            reward_stream.reward_mint.as_ref()
+        ],
+        bump = reward_stream.bump,
+    )]
+    pub reward_stream: Box<Account<'info, RewardStream>>,
+
+    #[account(
+        mut,
+        constraint = vault.key() == reward_stream.vault
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == reward_stream.reward_mint
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Take back what an ended stream's vault holds beyond what is owed
+///
+/// That is the funding left over once emission ended, and what was emitted
+/// while no one had joined the stream.
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(ctx: Context<ReclaimRewardStream>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    let reward_stream = &mut ctx.accounts.reward_stream;
+
+    if reward_stream.is_active(now) {
+        return Err(ErrorCode::RewardStreamNotEnded.into());
+    }
+
+    reward_stream.update_index(now)?;
+    let amount = reward_stream.reclaimable(ctx.accounts.vault.amount);
+
+    token::transfer(
+        ctx.accounts
+            .transfer_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        amount,
+    )?;
+
+    Ok(())
+}
+
+impl<'info> ReclaimRewardStream<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.vault.to_account_info(),
+                to: self.destination.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
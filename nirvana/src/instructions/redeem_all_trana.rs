@@ -0,0 +1,191 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ANA;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Key;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct RedeemAllTrana<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        constraint = mint_ana.key() == config.mint_ana
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_ana.mint == mint_ana.key(),
+        constraint = user_ana.owner == authority.key()
+    )]
+    pub user_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = treasury_ana.key() == config.treasury_ana
+    )]
+    pub treasury_ana: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Redeem everything vested across many trANA bonds in one transfer
+///
+/// The remaining accounts are every TranaMeta the bonds belong to, once each, followed by
+/// a UserTranaContract, its mint and the authority's token for it, for each bond.
+/// `indices` are the bonds' `userbond` indices, in the same order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemAllTrana<'info>>,
+    indices: Vec<u8>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let nirv_center = ctx.accounts.nirv_center.key();
+
+    let bond_accounts = indices.len().checked_mul(3).unwrap();
+    if indices.is_empty() || ctx.remaining_accounts.len() <= bond_accounts {
+        return Err(ErrorCode::InvalidTranaBatch.into());
+    }
+    let (meta_infos, bond_infos) = ctx
+        .remaining_accounts
+        .split_at(ctx.remaining_accounts.len() - bond_accounts);
+
+    let mut trana_metas = Vec::with_capacity(meta_infos.len());
+    for info in meta_infos {
+        let trana_meta: Account<'info, TranaMeta> = Account::try_from(info)?;
+        if trana_meta.nirv_center != nirv_center {
+            return Err(ErrorCode::UnauthorizedBondMetaAccess.into());
+        }
+
+        // A second copy would write over the first
+        if trana_metas
+            .iter()
+            .any(|other: &Account<TranaMeta>| other.key() == trana_meta.key())
+        {
+            return Err(ErrorCode::InvalidTranaBatch.into());
+        }
+        trana_metas.push(trana_meta);
+    }
+
+    let mut total_redeemed = ANA::ZERO;
+    for (index, bond) in indices.iter().zip(bond_infos.chunks(3)) {
+        let mut user_trana: Account<'info, UserTranaContract> = Account::try_from(&bond[0])?;
+        let trana_mint: Account<'info, Mint> = Account::try_from(&bond[1])?;
+        let trana_token: Account<'info, TokenAccount> = Account::try_from(&bond[2])?;
+
+        let (address, _) = Pubkey::find_program_address(
+            &[
+                b"userbond".as_ref(),
+                &[*index],
+                nirv_center.as_ref(),
+                user_trana.user.as_ref(),
+            ],
+            ctx.program_id,
+        );
+        if address != user_trana.key() {
+            return Err(ErrorCode::UnauthorizedBondAccount.into());
+        }
+
+        // Whoever holds the token owns the bond
+        if trana_mint.key() != user_trana.mint
+            || trana_token.mint != trana_mint.key()
+            || trana_token.owner != ctx.accounts.authority.key()
+            || trana_token.amount != 1
+        {
+            return Err(ErrorCode::UnauthorizedBondRedemption.into());
+        }
+
+        if user_trana.available {
+            return Err(ErrorCode::RedeemUnusedBond.into());
+        }
+
+        if user_trana.auto_stake {
+            return Err(ErrorCode::TranaBondAutoStakes.into());
+        }
+
+        let trana_meta = trana_metas
+            .iter_mut()
+            .find(|trana_meta| trana_meta.key() == user_trana.trana_meta)
+            .ok_or(ErrorCode::UnauthorizedBondMetaAccess)?;
+
+        let left_to_redeem = user_trana.get_left_to_redeem(now);
+        user_trana.update_redeemed(left_to_redeem);
+        trana_meta.sub_ana_outstanding(left_to_redeem);
+        total_redeemed += left_to_redeem;
+
+        // The bond is done with, so is its token
+        if user_trana.available {
+            token::burn(
+                ctx.accounts
+                    .burn_trana_token_context(bond[1].clone(), bond[2].clone()),
+                1,
+            )?;
+        }
+
+        user_trana.exit(ctx.program_id)?;
+    }
+
+    for trana_meta in trana_metas.iter() {
+        trana_meta.exit(ctx.program_id)?;
+    }
+
+    if total_redeemed.val == 0 {
+        msg!("Attempted redemption of already vested bonds");
+        return Ok(());
+    }
+
+    // Transfer everything redeemed at once
+    token::transfer(
+        ctx.accounts
+            .transfer_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        total_redeemed.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> RedeemAllTrana<'info> {
+    fn burn_trana_token_context(
+        &self,
+        trana_mint: AccountInfo<'info>,
+        trana_token: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: trana_mint,
+                to: trana_token,
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_ana.to_account_info(),
+                to: self.user_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -1,9 +1,9 @@
 use crate::errors::ErrorCode;
 use crate::state::*;
-use crate::utils::is_debug;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
 use anchor_spl::token;
+use anchor_spl::token::Burn;
 use anchor_spl::token::Mint;
 use anchor_spl::token::Token;
 use anchor_spl::token::TokenAccount;
@@ -38,17 +38,43 @@ pub struct RedeemTrana<'info> {
 
     #[account(
         mut,
-        constraint = user_trana.trana_meta == trana_meta.key(),
-        constraint = user_trana.user == authority.key()
+        constraint = user_trana.trana_meta == trana_meta.key()
     )]
     pub user_trana: Box<Account<'info, UserTranaContract>>,
 
     #[account(
         mut,
-        constraint = trana_meta.nirv_center == nirv_center.key()
+        constraint = trana_mint.key() == user_trana.mint
+    )]
+    pub trana_mint: Box<Account<'info, Mint>>,
+
+    /// Holds the bond token, whoever owns it owns the bond
+    #[account(
+        mut,
+        constraint = trana_token.mint == trana_mint.key(),
+        constraint = trana_token.owner == authority.key(),
+        constraint = trana_token.amount == 1 @ErrorCode::UnauthorizedBondRedemption
+    )]
+    pub trana_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key(),
+        seeds = [
+            b"trana_v1".as_ref(),
+            trana_meta.vesting_length_seconds.to_string().as_bytes(),
+            money_market.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
     )]
     pub trana_meta: Box<Account<'info, TranaMeta>>,
 
+    #[account(
+        constraint = money_market.key() == trana_meta.money_market
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
     #[account(
         mut,
         constraint = user_ana.mint == mint_ana.key(),
@@ -64,7 +90,6 @@ pub struct RedeemTrana<'info> {
 
     pub token_program: Program<'info, Token>,
 }
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<RedeemTrana>) -> Result<()> {
     ctx.accounts.trana_available()?;
 
@@ -73,6 +98,11 @@ pub fn handler(ctx: Context<RedeemTrana>) -> Result<()> {
 
     ctx.accounts.user_trana.update_redeemed(left_to_redeem);
 
+    // The bond is done with, so is its token
+    if ctx.accounts.user_trana.available {
+        token::burn(ctx.accounts.burn_trana_token_context(), 1)?;
+    }
+
     // there is nothing left in the bond
     if left_to_redeem.val == 0 {
         msg!("Attempted redemption of already vested bond");
@@ -99,9 +129,25 @@ impl<'info> RedeemTrana<'info> {
         if self.user_trana.available == true {
             return Err(ErrorCode::RedeemUnusedBond.into());
         }
+
+        // Opted in to staking, redeem_trana_and_stake has to be used
+        if self.user_trana.auto_stake {
+            return Err(ErrorCode::TranaBondAutoStakes.into());
+        }
         Ok(())
     }
 
+    fn burn_trana_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.trana_mint.to_account_info(),
+                to: self.trana_token.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
     fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
@@ -0,0 +1,189 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::Key;
+use anchor_spl::token;
+use anchor_spl::token::Burn;
+use anchor_spl::token::Mint;
+use anchor_spl::token::Token;
+use anchor_spl::token::TokenAccount;
+use anchor_spl::token::Transfer;
+
+#[derive(Accounts)]
+pub struct RedeemTranaAndStake<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = user_trana.trana_meta == trana_meta.key()
+    )]
+    pub user_trana: Box<Account<'info, UserTranaContract>>,
+
+    #[account(
+        mut,
+        constraint = trana_mint.key() == user_trana.mint
+    )]
+    pub trana_mint: Box<Account<'info, Mint>>,
+
+    /// Holds the bond token, whoever owns it owns the bond
+    #[account(
+        mut,
+        constraint = trana_token.mint == trana_mint.key(),
+        constraint = trana_token.owner == authority.key(),
+        constraint = trana_token.amount == 1 @ErrorCode::UnauthorizedBondRedemption
+    )]
+    pub trana_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key(),
+        seeds = [
+            b"trana_v1".as_ref(),
+            trana_meta.vesting_length_seconds.to_string().as_bytes(),
+            money_market.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump,
+    )]
+    pub trana_meta: Box<Account<'info, TranaMeta>>,
+
+    #[account(
+        constraint = money_market.key() == trana_meta.money_market
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        mut,
+        constraint = treasury_ana.key() == config.treasury_ana
+    )]
+    pub treasury_ana: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+/// Redeem the vested ANA of a trANA bond and stake it
+///
+/// Every reward stream joined is passed in the remaining accounts, as for stake_ana.
+pub fn handler(ctx: Context<RedeemTranaAndStake>) -> Result<()> {
+    ctx.accounts.trana_available()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let left_to_redeem = ctx.accounts.user_trana.get_left_to_redeem(now);
+
+    ctx.accounts.user_trana.update_redeemed(left_to_redeem);
+
+    // The bond is done with, so is its token
+    if ctx.accounts.user_trana.available {
+        token::burn(ctx.accounts.burn_trana_token_context(), 1)?;
+    }
+
+    // there is nothing left in the bond
+    if left_to_redeem.val == 0 {
+        msg!("Attempted redemption of already vested bond");
+        return Ok(());
+    }
+
+    // Stake it, staging the rewards first
+
+    ctx.accounts.user_reward_index.stake_ana(
+        left_to_redeem,
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now.unsigned_abs(),
+    )?;
+
+    // Transfer the left to redeem amount ANA to the stake pool
+    token::transfer(
+        ctx.accounts
+            .transfer_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        left_to_redeem.into(),
+    )?;
+
+    // Decrease outstanding ANA
+    ctx.accounts.trana_meta.sub_ana_outstanding(left_to_redeem);
+
+    Ok(())
+}
+
+impl<'info> RedeemTranaAndStake<'info> {
+    fn trana_available(&self) -> Result<()> {
+        // Bond is in initial state
+        if self.user_trana.available {
+            return Err(ErrorCode::RedeemUnusedBond.into());
+        }
+        Ok(())
+    }
+
+    fn burn_trana_token_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.trana_mint.to_account_info(),
+                to: self.trana_token.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
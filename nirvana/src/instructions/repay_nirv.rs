@@ -1,7 +1,6 @@
 use crate::errors::ErrorCode;
-use crate::numbers::NIRV;
+use crate::numbers::{ALMS, ANA, NIRV};
 use crate::state::*;
-use crate::utils::is_debug;
 use anchor_lang::prelude::*;
 use anchor_lang::Key;
 use anchor_spl::token::{self, *};
@@ -14,6 +13,13 @@ pub struct RepayNirv<'info> {
     pub nirv_center: Account<'info, NirvCenter>,
 
     #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
         seeds = [
             b"config_v3".as_ref(),
             nirv_center.key().as_ref()
@@ -46,21 +52,72 @@ pub struct RepayNirv<'info> {
     )]
     pub user_reward_index: Account<'info, UserRewardV2>,
 
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
     pub token_program: Program<'info, Token>,
 }
 
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<RepayNirv>, amount: u64) -> Result<()> {
     let nirv_balance = ctx.accounts.user_nirv.amount;
     if amount > nirv_balance {
         return Err(ErrorCode::RepayNIRVMoreThanHeld.into());
     }
 
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // the interest is owed before any of the debt can be repaid
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
     token::burn(ctx.accounts.burn_context(), amount)?;
 
     ctx.accounts
         .user_reward_index
         .repay_nirv(NIRV::from_u64(amount))?;
+    ctx.accounts
+        .config
+        .sub_borrowed_nirv(NIRV::from_u64(amount));
+
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
 
     Ok(())
 }
@@ -76,4 +133,15 @@ impl<'info> RepayNirv<'info> {
             },
         )
     }
+
+    fn mint_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
 }
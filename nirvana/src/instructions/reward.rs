@@ -44,6 +44,6 @@ pub fn handler(ctx: Context<Reward>) -> Result<()> {
     msg!("Total staked: {}", total_staked.val);
     ctx.accounts
         .config
-        .drop_prana_reward(ana_supply, total_staked, 24 * 60 * 60);
+        .drop_prana_reward(ana_supply, total_staked, 24 * 60 * 60)?;
     Ok(())
 }
@@ -1,9 +1,9 @@
 use crate::errors::ErrorCode;
-use crate::numbers::ANA;
+use crate::numbers::{PreciseNumber, ANA};
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
-use anchor_spl::token::TokenAccount;
+use anchor_spl::token;
+use anchor_spl::token::{Mint, MintTo, Token, TokenAccount};
 
 #[derive(Accounts)]
 pub struct RewardByTime<'info> {
@@ -12,6 +12,13 @@ pub struct RewardByTime<'info> {
     pub nirv_center: Account<'info, NirvCenter>,
 
     #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
         seeds = [
             b"config_v3".as_ref(),
             nirv_center.key().as_ref()
@@ -20,16 +27,53 @@ pub struct RewardByTime<'info> {
     )]
     pub config: Box<Account<'info, NirvCenterConfigV3>>,
 
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"emission_schedule".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = emission_schedule.bump,
+    )]
+    pub emission_schedule: Box<Account<'info, EmissionSchedule>>,
+
     #[account(
         constraint = mint_ana.key() == config.mint_ana,
     )]
     pub mint_ana: Account<'info, Mint>,
 
+    #[account(
+        mut,
+        constraint = mint_pre_ana.key() == config.mint_pre_ana
+    )]
+    pub mint_pre_ana: Box<Account<'info, Mint>>,
+
+    /// Receives the keeper bounty
+    #[account(
+        mut,
+        constraint = keeper_pre_ana.mint == mint_pre_ana.key()
+    )]
+    pub keeper_pre_ana: Box<Account<'info, TokenAccount>>,
+
     #[account(
         constraint = stake_pool_ana.key() == config.stake_pool_ana
     )]
     pub stake_pool_ana: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
 }
+
+/// Drop prANA rewards, paying the caller a bounty out of the drop
 pub fn handler(ctx: Context<RewardByTime>) -> Result<()> {
     let reward_interval_seconds = ctx.accounts.config.prana_reward_interval_seconds;
     let clock = Clock::get()?;
@@ -45,10 +89,44 @@ pub fn handler(ctx: Context<RewardByTime>) -> Result<()> {
     let ana_supply = ANA::from_u64(ctx.accounts.mint_ana.supply);
     let total_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
 
-    ctx.accounts
-        .config
-        .drop_prana_reward(ana_supply, total_staked, reward_interval_seconds);
+    // Drop what the schedule emitted since the last drop, however late the crank is
+    let emission = ctx
+        .accounts
+        .emission_schedule
+        .emission_between(ctx.accounts.config.time_of_last_prana_reward, ts);
+
+    let bounty = ctx.accounts.config.drop_prana_emission_with_keeper_bounty(
+        ana_supply,
+        total_staked,
+        emission,
+    )?;
     ctx.accounts.config.time_of_last_prana_reward = ts;
+    ctx.accounts.config.prana_reward_rate =
+        PreciseNumber::from_decimal(ctx.accounts.emission_schedule.rate_at(ts));
+
+    if bounty != ANA::ZERO {
+        ctx.accounts.global_history.pay_keeper_bounty(bounty);
+
+        token::mint_to(
+            ctx.accounts
+                .mint_bounty_context()
+                .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+            bounty.into(),
+        )?;
+    }
 
     Ok(())
 }
+
+impl<'info> RewardByTime<'info> {
+    fn mint_bounty_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_pre_ana.to_account_info(),
+                to: self.keeper_pre_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
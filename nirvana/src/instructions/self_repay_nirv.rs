@@ -0,0 +1,386 @@
+use crate::{
+    errors::ErrorCode,
+    numbers::{Decimalable, PreciseNumber, ALMS, ANA, NIRV},
+    price_math::{calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use rust_decimal::prelude::*;
+
+#[event]
+struct SelfRepayNirvEvent {
+    owner: Pubkey,
+    prana_realized: u64,
+    nirv_repaid: u64,
+    remaining_borrowed_nirv: u64,
+}
+
+#[derive(Accounts)]
+pub struct SelfRepayNirv<'info> {
+    /// Anyone can crank a self-repaying loan
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_pre_ana.key() == config.mint_pre_ana
+    )]
+    pub mint_pre_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    /// Receives the value left over once the loan is repaid
+    #[account(
+        mut,
+        constraint = user_nirv.mint == mint_nirv.key(),
+        constraint = user_nirv.owner == user_reward_index.owner
+    )]
+    pub user_nirv: Box<Account<'info, TokenAccount>>,
+
+    /// Token account for treasury NIRV
+    #[account(
+        mut,
+        constraint = treasury_nirv.key() == money_market.token_account,
+        constraint = treasury_nirv.mint == mint_nirv.key(),
+    )]
+    pub treasury_nirv: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            mint_nirv.key().as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = prana_fee_account.key() == config.prana_fee_account
+    )]
+    pub prana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Repay a self-repaying NIRV loan with the user's prANA rewards
+///
+/// The prANA is realized at the floor with treasury NIRV and the ANA is sold straight back,
+/// so only the value above the floor is used to repay the loan.
+/// Any value beyond the debt is sent to the user as NIRV.
+pub fn handler(ctx: Context<SelfRepayNirv>) -> Result<()> {
+    ctx.accounts.can_self_repay()?;
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // the debt is repaid including interest
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
+    let (prana_rewards, prana_fee) = ctx.accounts.user_reward_index.claim_prana_rewards(
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+        total_alms_staked,
+    )?;
+
+    // Realize the prANA at the floor, rounding up
+    let realize_cost = prana_rewards
+        .to_decimal()
+        .checked_mul(ctx.accounts.price_field.floor_price.into())
+        .unwrap()
+        .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+    let realize_cost = NIRV::from_decimal(realize_cost);
+
+    ctx.accounts
+        .price_field
+        .increase_supply_with_no_price_impact(prana_rewards);
+    let realized_supply = ANA::from_u64(ctx.accounts.mint_ana.supply) + prana_rewards;
+
+    // Sell the realized ANA
+    let (ana_sold, sell_fee) =
+        ctx.accounts
+            .config
+            .collect_ana_swap_fee(false, prana_rewards, total_alms_staked)?;
+    let total_proceeds_d = ctx.accounts.total_proceeds(now, realized_supply, ana_sold);
+    let total_proceeds = total_proceeds_d.mantissa().to_u64().unwrap();
+
+    if total_proceeds <= realize_cost.val {
+        return Err(ErrorCode::SelfRepayNotProfitable.into());
+    }
+
+    let value = NIRV::from_u64(total_proceeds - realize_cost.val);
+    let nirv_repaid = ctx.accounts.user_reward_index.repay_nirv_up_to(value);
+    let excess = value.val.checked_sub(nirv_repaid.val).unwrap();
+    ctx.accounts.config.sub_borrowed_nirv(nirv_repaid);
+
+    ctx.accounts
+        .update_history(total_proceeds_d, prana_rewards, realized_supply, ana_sold);
+
+    // Collect the prANA fee
+    token::mint_to(
+        ctx.accounts
+            .mint_prana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        prana_fee.into(),
+    )?;
+
+    // Only the sell fee is left of the realized ANA
+    token::mint_to(
+        ctx.accounts
+            .mint_ana_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        sell_fee.into(),
+    )?;
+
+    // Burn the NIRV repaid out of the treasury
+    token::burn(
+        ctx.accounts
+            .burn_nirv_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        nirv_repaid.into(),
+    )?;
+
+    // Transfer the rest of the value to the user
+    token::transfer(
+        ctx.accounts
+            .transfer_nirv_to_user_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        excess,
+    )?;
+
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_nirv_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
+
+    emit!(SelfRepayNirvEvent {
+        owner: ctx.accounts.user_reward_index.owner,
+        prana_realized: prana_rewards.into(),
+        nirv_repaid: nirv_repaid.into(),
+        remaining_borrowed_nirv: ctx.accounts.user_reward_index.borrowed_nirv.into(),
+    });
+
+    Ok(())
+}
+
+impl<'info> SelfRepayNirv<'info> {
+    fn can_self_repay(&self) -> Result<()> {
+        if !self.user_reward_index.self_repaying {
+            return Err(ErrorCode::SelfRepayingNotEnabled.into());
+        }
+
+        if self.user_reward_index.borrowed_nirv == NIRV::ZERO {
+            return Err(ErrorCode::NoNirvDebtToRepay.into());
+        }
+
+        if !self.money_market.for_prana {
+            return Err(ErrorCode::TokenAccountNotForPrana.into());
+        }
+
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        Ok(())
+    }
+
+    fn total_proceeds(&self, now: u64, supply: ANA, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Selling, so round price down
+        let mut total_proceeds_d = calc_total_cost_for_amount(
+            supply,
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_field.to_owned().into_inner(),
+            false,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::ToZero);
+
+        // Scale the precision of the underyling token
+        total_proceeds_d.rescale(money_scale.into());
+
+        total_proceeds_d
+    }
+
+    fn update_history(
+        &mut self,
+        total_proceeds_d: Decimal,
+        prana_realized: ANA,
+        realized_supply: ANA,
+        ana_sold: ANA,
+    ) {
+        let round_dollars = total_proceeds_d
+            .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+            .to_u64()
+            .unwrap();
+        let new_ana_supply = realized_supply - ana_sold;
+
+        self.global_history.prana_executed += prana_realized;
+        self.global_history.sell_ana(round_dollars, ana_sold);
+
+        // Update the new price of ANA
+        // NOTE: This does not include the bootstrapping offset
+        let price_for_unit = self.price_field.price_for_supply(new_ana_supply);
+        self.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+
+        self.price_field
+            .reset_slippage_start_point_if_needed(new_ana_supply);
+    }
+
+    fn mint_prana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_pre_ana.to_account_info(),
+                to: self.prana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_ana_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn burn_nirv_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.treasury_nirv.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_nirv_to_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_nirv.to_account_info(),
+                to: self.user_nirv.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_nirv_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,32 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCompoundKeeper<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Account<'info, UserRewardV2>,
+}
+
+/// Let a keeper compound rewards on the owner's behalf
+///
+/// The keeper pays for realizing prANA from the owner's money market token account,
+/// so the owner also approves the keeper as a delegate on that account.
+/// Set the keeper to the default pubkey to opt out.
+pub fn handler(ctx: Context<SetCompoundKeeper>, keeper: Pubkey) -> Result<()> {
+    ctx.accounts.user_reward_index.compound_keeper = keeper;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Account<'info, UserRewardV2>,
+}
+
+/// Approve a delegate to claim, compound and restake for the owner
+///
+/// The delegate can never unstake or borrow, and claimed prANA only goes to the owner.
+/// Set the delegate to the default pubkey to revoke it.
+pub fn handler(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+    ctx.accounts.user_reward_index.delegate = delegate;
+
+    Ok(())
+}
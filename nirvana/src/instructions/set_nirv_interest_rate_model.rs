@@ -0,0 +1,66 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{CoarseNumber, ANA};
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct SetNirvInterestRateModel<'info> {
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    #[account(
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    pub signer: Signer<'info>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<SetNirvInterestRateModel>,
+    base_rate: CoarseNumber,
+    rate_slope: CoarseNumber,
+    optimal_utilization: CoarseNumber,
+    jump_rate_slope: CoarseNumber,
+) -> Result<()> {
+    require!(
+        optimal_utilization.val <= CoarseNumber::DENOMINATOR,
+        ErrorCode::InvalidNirvRateModel
+    );
+
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // interest up to now is charged at the old rates
+    let config = &mut ctx.accounts.config;
+    config.accrue_nirv_interest(now, total_ana_staked, ctx.accounts.price_field.floor_price);
+
+    config.nirv_base_rate = base_rate;
+    config.nirv_rate_slope = rate_slope;
+    config.nirv_optimal_utilization = optimal_utilization;
+    config.nirv_jump_rate_slope = jump_rate_slope;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::errors::ErrorCode;
+use crate::numbers::CoarseNumber;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetNirvLiquidationParams<'info> {
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    pub signer: Signer<'info>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<SetNirvLiquidationParams>,
+    close_factor: CoarseNumber,
+    liquidation_bonus: CoarseNumber,
+) -> Result<()> {
+    require!(
+        close_factor.val <= CoarseNumber::DENOMINATOR,
+        ErrorCode::InvalidNirvLiquidationParams
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.nirv_close_factor = close_factor;
+    config.nirv_liquidation_bonus = liquidation_bonus;
+
+    Ok(())
+}
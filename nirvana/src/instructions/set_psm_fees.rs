@@ -0,0 +1,41 @@
+use crate::errors::ErrorCode;
+use crate::numbers::CoarseNumber;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPsmFees<'info> {
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    pub signer: Signer<'info>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<SetPsmFees>,
+    mint_fee: CoarseNumber,
+    redeem_fee: CoarseNumber,
+) -> Result<()> {
+    require!(
+        mint_fee.val <= CoarseNumber::DENOMINATOR && redeem_fee.val <= CoarseNumber::DENOMINATOR,
+        ErrorCode::InvalidPsmFees
+    );
+
+    let config = &mut ctx.accounts.config;
+
+    config.psm_mint_fee = mint_fee;
+    config.psm_redeem_fee = redeem_fee;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::numbers::NIRV;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPsmMarket<'info> {
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            money_market.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Account<'info, MoneyMarket>,
+
+    pub signer: Signer<'info>,
+}
+
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(ctx: Context<SetPsmMarket>, for_psm: bool, debt_ceiling: NIRV) -> Result<()> {
+    let money_market = &mut ctx.accounts.money_market;
+
+    money_market.for_psm = for_psm;
+    money_market.psm_debt_ceiling = debt_ceiling;
+
+    Ok(())
+}
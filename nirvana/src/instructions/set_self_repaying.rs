@@ -0,0 +1,28 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetSelfRepaying<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == authority.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Account<'info, UserRewardV2>,
+}
+
+/// Opt in or out of repaying borrowed NIRV with prANA rewards
+pub fn handler(ctx: Context<SetSelfRepaying>, self_repaying: bool) -> Result<()> {
+    ctx.accounts.user_reward_index.self_repaying = self_repaying;
+
+    Ok(())
+}
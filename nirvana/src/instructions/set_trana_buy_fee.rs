@@ -1,6 +1,6 @@
 use crate::numbers::CoarseNumber;
 use crate::state::*;
-use crate::utils::{admin, is_debug};
+use crate::utils::admin;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -21,7 +21,6 @@ pub struct SetTranaBuyFee<'info> {
 }
 
 #[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<SetTranaBuyFee>, fee: CoarseNumber) -> Result<()> {
     let config = &mut ctx.accounts.config;
 
@@ -0,0 +1,33 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ANA;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTranaCapacity<'info> {
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key() @ErrorCode::UnauthorizedBondMetaAccess
+    )]
+    pub trana_meta: Account<'info, TranaMeta>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    pub signer: Signer<'info>,
+}
+
+/// Set the total, per-purchase and per-user ANA limits, zero for no limit
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<SetTranaCapacity>,
+    capacity: ANA,
+    max_per_purchase: ANA,
+    max_per_user: ANA,
+) -> Result<()> {
+    let trana_meta = &mut ctx.accounts.trana_meta;
+    trana_meta.capacity = capacity;
+    trana_meta.max_per_purchase = max_per_purchase;
+    trana_meta.max_per_user = max_per_user;
+    Ok(())
+}
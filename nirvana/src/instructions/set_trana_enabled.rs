@@ -1,6 +1,6 @@
 use crate::errors::ErrorCode;
 use crate::state::*;
-use crate::utils::{admin, is_debug};
+use crate::utils::admin;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -17,7 +17,6 @@ pub struct SetTranaEnabled<'info> {
 }
 
 #[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<SetTranaEnabled>, is_enabled: bool) -> Result<()> {
     let trana_meta = &mut ctx.accounts.trana_meta;
     trana_meta.enabled = is_enabled;
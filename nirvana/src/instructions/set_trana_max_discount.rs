@@ -1,7 +1,7 @@
 use crate::errors::ErrorCode;
 use crate::numbers::PreciseNumber;
 use crate::state::*;
-use crate::utils::{admin, is_debug};
+use crate::utils::admin;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -18,7 +18,6 @@ pub struct SetTranaMaxDiscount<'info> {
 }
 
 #[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<SetTranaMaxDiscount>, max_discount_ratio: PreciseNumber) -> Result<()> {
     let trana_meta = &mut ctx.accounts.trana_meta;
     trana_meta.max_discount_ratio = max_discount_ratio;
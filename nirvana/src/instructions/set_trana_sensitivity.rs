@@ -1,7 +1,7 @@
 use crate::errors::ErrorCode;
 use crate::numbers::PreciseNumber;
 use crate::state::*;
-use crate::utils::{admin, is_debug};
+use crate::utils::admin;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -18,7 +18,6 @@ pub struct SetBondBcv<'info> {
 }
 
 #[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
-#[access_control(is_debug(&ctx.accounts.nirv_center))]
 pub fn handler(ctx: Context<SetBondBcv>, sensitivity: PreciseNumber) -> Result<()> {
     let trana_meta = &mut ctx.accounts.trana_meta;
     trana_meta.sensitivity = sensitivity;
@@ -0,0 +1,43 @@
+use crate::errors::ErrorCode;
+use crate::numbers::CoarseNumber;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTranaVesting<'info> {
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key() @ErrorCode::UnauthorizedBondMetaAccess
+    )]
+    pub trana_meta: Account<'info, TranaMeta>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    pub signer: Signer<'info>,
+}
+
+/// Set the cliff, the release steps and the early exit terms of bonds bought from now on
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(
+    ctx: Context<SetTranaVesting>,
+    cliff_seconds: u64,
+    release_step_seconds: u64,
+    early_exit_enabled: bool,
+    early_exit_penalty: CoarseNumber,
+) -> Result<()> {
+    let trana_meta = &mut ctx.accounts.trana_meta;
+
+    if cliff_seconds > trana_meta.vesting_length_seconds
+        || release_step_seconds > trana_meta.vesting_length_seconds
+        || early_exit_penalty.val > CoarseNumber::DENOMINATOR
+    {
+        return Err(ErrorCode::InvalidTranaVesting.into());
+    }
+
+    trana_meta.cliff_seconds = cliff_seconds;
+    trana_meta.release_step_seconds = release_step_seconds;
+    trana_meta.early_exit_enabled = early_exit_enabled;
+    trana_meta.early_exit_penalty = early_exit_penalty;
+    Ok(())
+}
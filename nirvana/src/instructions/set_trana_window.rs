@@ -0,0 +1,30 @@
+use crate::errors::ErrorCode;
+use crate::state::*;
+use crate::utils::admin;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetTranaWindow<'info> {
+    #[account(
+        mut,
+        constraint = trana_meta.nirv_center == nirv_center.key() @ErrorCode::UnauthorizedBondMetaAccess
+    )]
+    pub trana_meta: Account<'info, TranaMeta>,
+
+    pub nirv_center: Account<'info, NirvCenter>,
+
+    pub signer: Signer<'info>,
+}
+
+/// Set when sales open and close, a close time of zero never closes
+#[access_control(admin(&ctx.accounts.nirv_center, &ctx.accounts.signer))]
+pub fn handler(ctx: Context<SetTranaWindow>, open_time: i64, close_time: i64) -> Result<()> {
+    if close_time != 0 && close_time <= open_time {
+        return Err(ErrorCode::InvalidTranaWindow.into());
+    }
+
+    let trana_meta = &mut ctx.accounts.trana_meta;
+    trana_meta.open_time = open_time;
+    trana_meta.close_time = close_time;
+    Ok(())
+}
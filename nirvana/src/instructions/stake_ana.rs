@@ -63,13 +63,26 @@ pub struct StakeAna<'info> {
 
     pub token_program: Program<'info, Token>,
 }
+/// Stake ANA
+///
+/// Every reward stream joined is passed in the remaining accounts, as pairs of
+/// RewardStream and StreamCheckpoint, to settle them with the new stake.
 pub fn handler(ctx: Context<StakeAna>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
     // update how much the user has staked
     ctx.accounts.user_reward_index.stake_ana(
         ANA::from_u64(amount),
         &mut ctx.accounts.config,
         &ctx.accounts.price_field,
-    );
+    )?;
+
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
 
     token::transfer(ctx.accounts.transfer_context(), amount)?;
 
@@ -0,0 +1,109 @@
+use crate::errors::ErrorCode;
+use crate::numbers::ANA;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct StakeAnaFor<'info> {
+    /// Pays the ANA, gets no claim on it
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    /// The beneficiary's position
+    #[account(
+        mut,
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            user_reward_index.owner.as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = user_token_ana.mint == config.mint_ana,
+        constraint = user_token_ana.owner == authority.key()
+    )]
+    pub user_token_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Stake the signer's ANA into another user's position
+///
+/// Only the beneficiary can unstake it.
+pub fn handler(ctx: Context<StakeAnaFor>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // ANA given to the sANA vault would reprice its shares
+    let (sana_vault, _) = Pubkey::find_program_address(
+        &[
+            b"sana_vault".as_ref(),
+            ctx.accounts.nirv_center.key().as_ref(),
+        ],
+        ctx.program_id,
+    );
+    if ctx.accounts.user_reward_index.owner == sana_vault {
+        return Err(ErrorCode::CannotStakeForSanaVault.into());
+    }
+
+    ctx.accounts.user_reward_index.stake_ana(
+        ANA::from_u64(amount),
+        &ctx.accounts.config,
+        &ctx.accounts.price_field,
+    )?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    token::transfer(ctx.accounts.transfer_context(), amount)?;
+
+    Ok(())
+}
+
+impl<'info> StakeAnaFor<'info> {
+    fn transfer_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.user_token_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+}
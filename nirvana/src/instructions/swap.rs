@@ -141,7 +141,7 @@ pub fn handler(
     let (ana_less_fees, fees) =
         ctx.accounts
             .config
-            .collect_ana_swap_fee(is_buy, amount_of_ana, total_alms_staked);
+            .collect_ana_swap_fee(is_buy, amount_of_ana, total_alms_staked)?;
 
     let now = ctx.accounts.now(clock_override);
     let is_bootstrapping = ctx.accounts.config.is_bootstrapping(now);
@@ -5,6 +5,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::Key;
 use anchor_spl::token;
 use anchor_spl::token::Mint;
+use anchor_spl::token::MintTo;
 use anchor_spl::token::Token;
 use anchor_spl::token::TokenAccount;
 use anchor_spl::token::Transfer;
@@ -84,6 +85,18 @@ pub struct UnstakeAna<'info> {
     )]
     pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
 
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
     pub token_program: Program<'info, Token>,
 }
 
@@ -94,6 +107,19 @@ pub fn handler(ctx: Context<UnstakeAna>, amount: u64) -> Result<()> {
     let amount = ANA::from_u64(amount);
 
     let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    // the remaining stake has to back the debt including interest
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_field.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
 
     // decrease user's staked amount
     let (amount_less_fee, fee) = ctx.accounts.user_reward_index.unstake_ana(
@@ -103,6 +129,14 @@ pub fn handler(ctx: Context<UnstakeAna>, amount: u64) -> Result<()> {
         total_alms_staked,
     )?;
 
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
     // Transfer ANA to user
     token::transfer(
         ctx.accounts
@@ -119,6 +153,14 @@ pub fn handler(ctx: Context<UnstakeAna>, amount: u64) -> Result<()> {
         fee.into(),
     )?;
 
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
+
     Ok(())
 }
 
@@ -132,6 +174,17 @@ impl<'info> UnstakeAna<'info> {
         Ok(())
     }
 
+    fn mint_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
     fn transfer_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
         CpiContext::new(
             self.token_program.to_account_info(),
@@ -0,0 +1,345 @@
+use crate::{
+    errors::ErrorCode,
+    numbers::{PreciseNumber, ALMS, ANA},
+    price_math::{calc_total_cost_for_amount, PriceCalculator},
+    state::*,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use rust_decimal::prelude::*;
+
+#[derive(Accounts)]
+pub struct UnstakeAndSell<'info> {
+    pub signer: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        mut,
+        constraint = mint_ana.key() == config.mint_ana,
+    )]
+    pub mint_ana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_reward_index.owner == signer.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump = user_reward_index.bump
+    )]
+    pub user_reward_index: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: User must sign to move funds
+    #[account(
+        mut,
+        constraint = user_money_token_account.mint == money_market.mint
+    )]
+    pub user_money_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Token account for treasury money
+    #[account(
+        mut,
+        constraint = treasury_token_account.key() == money_market.token_account,
+        constraint = treasury_token_account.mint == money_market.mint,
+    )]
+    pub treasury_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        has_one = nirv_center,
+        seeds = [
+            b"mm1".as_ref(),
+            treasury_token_account.mint.as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = money_market.bump,
+    )]
+    pub money_market: Box<Account<'info, MoneyMarket>>,
+
+    #[account(
+        mut,
+        has_one = nirv_center,
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_calculator.bump
+    )]
+    pub price_calculator: Box<Account<'info, PriceFieldV2>>,
+
+    /// CHECK: Key is matched to NirvCenter
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account,
+        constraint = ana_fee_account.mint == mint_ana.key()
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = history.authority == signer.key(),
+        constraint = history.nirv_center == nirv_center.key(),
+        seeds = [
+            b"history".as_ref(),
+            nirv_center.key().as_ref(),
+            signer.key().as_ref()
+        ],
+        bump = history.bump,
+    )]
+    pub history: Box<Account<'info, History>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"globalhistory".as_ref(),
+            nirv_center.key().as_ref(),
+        ],
+        bump = global_history.bump,
+    )]
+    pub global_history: Box<Account<'info, GlobalHistory>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms,
+        constraint = stake_pool_alms.mint == config.mint_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = mint_nirv.key() == config.mint_nirv
+    )]
+    pub mint_nirv: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = nirv_fee_account.key() == config.nirv_fee_account
+    )]
+    pub nirv_fee_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Unstake ANA and sell it without it passing through the user's wallet
+///
+/// The unstake and sell fees are charged together on the ANA unstaked.
+pub fn handler(ctx: Context<UnstakeAndSell>, amount: u64, expected_total_cost: u64) -> Result<()> {
+    ctx.accounts.can_swap(amount)?;
+
+    let amount_of_ana = ANA::from_u64(amount);
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+    let total_ana_staked = ANA::from_u64(ctx.accounts.stake_pool_ana.amount);
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+    let is_bootstrapping = ctx.accounts.config.is_bootstrapping(now);
+
+    // the remaining stake has to back the debt including interest
+    ctx.accounts.config.accrue_nirv_interest(
+        now,
+        total_ana_staked,
+        ctx.accounts.price_calculator.floor_price,
+    );
+    let interest = ctx
+        .accounts
+        .user_reward_index
+        .accrue_nirv_interest(&mut ctx.accounts.config, total_alms_staked)?;
+
+    // decrease user's staked amount
+    ctx.accounts.user_reward_index.withdraw_stake(
+        amount_of_ana,
+        &ctx.accounts.config,
+        &ctx.accounts.price_calculator,
+    )?;
+
+    let (ana_less_fees, fees) = ctx
+        .accounts
+        .config
+        .collect_ana_unstake_and_sell_fee(amount_of_ana, total_alms_staked)?;
+
+    let total_cost_d = ctx.accounts.total_cost(now, ana_less_fees);
+    msg!("Total cost: {}", total_cost_d);
+
+    // update history
+    let round_dollars = total_cost_d
+        .round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero)
+        .to_u64()
+        .unwrap();
+
+    ctx.accounts
+        .update_history(round_dollars, is_bootstrapping, ana_less_fees);
+
+    // Convert to integer
+    let total_cost = total_cost_d.mantissa().to_u64().unwrap();
+
+    if expected_total_cost > total_cost {
+        return Err(ErrorCode::SlippageExceededForSell.into());
+    }
+
+    // Update the new price of ANA
+    // NOTE: This does not include the bootstrapping offset
+    let new_ana_supply = ANA::from_u64(ctx.accounts.mint_ana.supply) - ana_less_fees;
+    let price_for_unit = ctx
+        .accounts
+        .price_calculator
+        .price_for_supply(new_ana_supply);
+    ctx.accounts.config.current_ana_price_usd = PreciseNumber::from_decimal(price_for_unit);
+    ctx.accounts
+        .price_calculator
+        .reset_slippage_start_point_if_needed(new_ana_supply);
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.user_reward_index,
+        now,
+    )?;
+
+    // Burn ANA
+    token::burn(
+        ctx.accounts
+            .burn_ana_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        ana_less_fees.into(),
+    )?;
+
+    // Transfer fee to account
+    token::transfer(
+        ctx.accounts
+            .transfer_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        fees.into(),
+    )?;
+
+    // Transfer payment
+    token::transfer(
+        ctx.accounts
+            .refund_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        total_cost,
+    )?;
+
+    // Transfer NIRV interest to fee account
+    token::mint_to(
+        ctx.accounts
+            .mint_nirv_to_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        interest.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> UnstakeAndSell<'info> {
+    fn can_swap(&self, amount: u64) -> Result<()> {
+        if !self.money_market.enabled || !self.money_market.for_amm {
+            return Err(error!(ErrorCode::MoneyMarketNotEnabled));
+        }
+
+        if amount > self.user_reward_index.staked_amount.val {
+            return Err(ErrorCode::InsufficientStakedANAToUnstake.into());
+        }
+
+        Ok(())
+    }
+
+    fn update_history(&mut self, round_dollars: u64, is_bootstrapping: bool, amount_of_ana: ANA) {
+        self.global_history.sell_ana(round_dollars, amount_of_ana);
+
+        if is_bootstrapping {
+            self.history
+                .sell_during_bootstrap(round_dollars, amount_of_ana);
+        } else {
+            self.history.sell(round_dollars);
+        }
+    }
+
+    fn total_cost(&self, now: u64, amount_of_ana: ANA) -> Decimal {
+        let bootstrap_params = self.config.to_bootstrap_params();
+        let money_scale = self.money_market.decimals;
+        let mut price_offset = bootstrap_params.current_offset(now);
+        price_offset.rescale(money_scale.into());
+
+        // Selling, so round price down
+        let mut total_cost_d = calc_total_cost_for_amount(
+            ANA::from_u64(self.mint_ana.supply),
+            amount_of_ana,
+            self.money_market.risk_free_value_coefficient,
+            &self.price_calculator.to_owned().into_inner(),
+            false,
+            price_offset,
+        )
+        .round_dp_with_strategy(money_scale.into(), RoundingStrategy::ToZero);
+
+        // Scale the precision of the underyling token
+        total_cost_d.rescale(money_scale.into());
+
+        total_cost_d
+    }
+
+    fn burn_ana_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_ana.to_account_info(),
+                to: self.stake_pool_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn refund_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.treasury_token_account.to_account_info(),
+                to: self.user_money_token_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn mint_nirv_to_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, MintTo<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            MintTo {
+                mint: self.mint_nirv.to_account_info(),
+                to: self.nirv_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
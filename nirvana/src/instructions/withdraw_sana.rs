@@ -0,0 +1,190 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{ALMS, ANA};
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+
+#[derive(Accounts)]
+pub struct WithdrawSana<'info> {
+    pub authority: Signer<'info>,
+
+    pub nirv_center: Box<Account<'info, NirvCenter>>,
+
+    #[account(
+        constraint = nirv_center_authority.key() == nirv_center.signer_authority
+    )]
+    /// CHECK - Just a pubkey
+    pub nirv_center_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"config_v3".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, NirvCenterConfigV3>>,
+
+    #[account(
+        has_one = nirv_center,
+        has_one = mint_sana,
+        seeds = [
+            b"sana_vault".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = sana_vault.bump,
+    )]
+    pub sana_vault: Box<Account<'info, SanaVault>>,
+
+    #[account(mut)]
+    pub mint_sana: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = vault_reward.owner == sana_vault.key(),
+        seeds = [
+            b"userreward_v2".as_ref(),
+            nirv_center.key().as_ref(),
+            sana_vault.key().as_ref()
+        ],
+        bump = vault_reward.bump
+    )]
+    pub vault_reward: Box<Account<'info, UserRewardV2>>,
+
+    #[account(
+        mut,
+        constraint = user_sana.mint == mint_sana.key(),
+        constraint = user_sana.owner == authority.key()
+    )]
+    pub user_sana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_ana.mint == config.mint_ana
+    )]
+    pub user_token_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = stake_pool_ana.key() == config.stake_pool_ana
+    )]
+    pub stake_pool_ana: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = stake_pool_alms.key() == config.stake_pool_alms
+    )]
+    pub stake_pool_alms: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = ana_fee_account.key() == config.ana_fee_account
+    )]
+    pub ana_fee_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = price_field.nirv_center == nirv_center.key(),
+        seeds = [
+            b"pf2".as_ref(),
+            nirv_center.key().as_ref()
+        ],
+        bump = price_field.bump
+    )]
+    pub price_field: Box<Account<'info, PriceFieldV2>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Burn sANA and unstake its share of the vault's ANA
+///
+/// The unstake fee applies as it would to any staker.
+/// The vault never borrows NIRV, so there is no debt to keep covered.
+pub fn handler(ctx: Context<WithdrawSana>, sana_amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp.unsigned_abs();
+
+    if sana_amount > ctx.accounts.user_sana.amount {
+        return Err(ErrorCode::InsufficientSana.into());
+    }
+
+    let total_alms_staked = ALMS::from_u64(ctx.accounts.stake_pool_alms.amount);
+
+    let amount = SanaVault::ana_for_withdrawal(
+        sana_amount,
+        ctx.accounts.vault_reward.staked_amount,
+        ctx.accounts.mint_sana.supply,
+    );
+
+    if amount == ANA::ZERO {
+        return Err(ErrorCode::SanaAmountTooSmall.into());
+    }
+
+    let (amount_less_fee, fee) = ctx.accounts.vault_reward.unstake_ana(
+        amount,
+        &mut ctx.accounts.config,
+        &ctx.accounts.price_field,
+        total_alms_staked,
+    )?;
+
+    // Every reward stream joined is passed in the remaining accounts
+    settle_reward_streams(
+        ctx.remaining_accounts,
+        ctx.program_id,
+        &ctx.accounts.vault_reward,
+        now,
+    )?;
+
+    token::burn(ctx.accounts.burn_sana_context(), sana_amount)?;
+
+    // Transfer ANA to user
+    token::transfer(
+        ctx.accounts
+            .transfer_user_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        amount_less_fee.into(),
+    )?;
+
+    // Transfer ANA to fee account
+    token::transfer(
+        ctx.accounts
+            .transfer_fee_context()
+            .with_signer(&[&ctx.accounts.nirv_center.authority_seeds()]),
+        fee.into(),
+    )?;
+
+    Ok(())
+}
+
+impl<'info> WithdrawSana<'info> {
+    fn burn_sana_context(&self) -> CpiContext<'_, '_, '_, 'info, Burn<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.mint_sana.to_account_info(),
+                to: self.user_sana.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_user_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.user_token_ana.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+
+    fn transfer_fee_context(&self) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        CpiContext::new(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.stake_pool_ana.to_account_info(),
+                to: self.ana_fee_account.to_account_info(),
+                authority: self.nirv_center_authority.to_account_info(),
+            },
+        )
+    }
+}
@@ -0,0 +1,192 @@
+use rust_decimal::prelude::*;
+
+use crate::numbers::{Decimalable, ANA, NIRV};
+
+/// Most borrow, buy and stake rounds a single lever_up will run
+pub const MAX_LEVER_STEPS: u8 = 4;
+
+/// Leverage of a staked position
+///
+/// Leverage is the floor value of the staked ANA over the equity,
+/// where the equity is that floor value less the NIRV debt.
+/// 100 ANA staked at a $1 floor with 50 NIRV borrowed is 2x levered.
+pub fn leverage(staked: ANA, borrowed: NIRV, floor: Decimal) -> Decimal {
+    let collateral = staked.to_decimal().checked_mul(floor).unwrap();
+    let equity = collateral.checked_sub(borrowed.to_decimal()).unwrap();
+
+    if equity <= Decimal::ZERO {
+        return Decimal::MAX;
+    }
+
+    collateral.checked_div(equity).unwrap()
+}
+
+/// Highest leverage allowed by the max NIRV loan ratio
+///
+/// A loan ratio of r allows borrowing r of the collateral, so the
+/// equity is (1 - r) of it. 50% allows 2x while 75% allows 4x.
+pub fn max_leverage(max_loan_ratio: Decimal) -> Decimal {
+    if max_loan_ratio >= Decimal::ONE {
+        return Decimal::MAX;
+    }
+
+    Decimal::ONE
+        .checked_div(Decimal::ONE.checked_sub(max_loan_ratio).unwrap())
+        .unwrap()
+}
+
+/// NIRV borrowed per ANA staked
+///
+/// The buy fee is taken from the ANA bought and the origination fee from the
+/// NIRV borrowed, so both raise the debt taken on for each ANA that is staked.
+pub fn effective_price(price: Decimal, buy_fee: Decimal, origination_fee: Decimal) -> Decimal {
+    let kept = Decimal::ONE
+        .checked_sub(buy_fee)
+        .unwrap()
+        .checked_mul(Decimal::ONE.checked_sub(origination_fee).unwrap())
+        .unwrap();
+
+    price.checked_div(kept).unwrap_or(Decimal::MAX)
+}
+
+/// How much ANA to stake to reach a target leverage
+///
+/// Each ANA bought costs `price` NIRV of debt, fees included, but only adds
+/// its floor value as collateral, so every purchase eats into the equity.
+/// Solving `(S + x)F / (SF - D - x(P - F)) = L` for x gives
+///
+/// x = (L(SF - D) - SF) / (F + L(P - F))
+///
+/// The price moves as ANA is bought, so lever_up repeats this with
+/// the new price until the target is reached.
+pub fn ana_to_reach_leverage(
+    staked: ANA,
+    borrowed: NIRV,
+    floor: Decimal,
+    price: Decimal,
+    target_leverage: Decimal,
+) -> ANA {
+    let collateral = staked.to_decimal().checked_mul(floor).unwrap();
+    let equity = collateral.checked_sub(borrowed.to_decimal()).unwrap();
+
+    if equity <= Decimal::ZERO || price < floor {
+        return ANA::ZERO;
+    }
+
+    let numerator = target_leverage
+        .checked_mul(equity)
+        .unwrap()
+        .checked_sub(collateral)
+        .unwrap();
+
+    if numerator <= Decimal::ZERO {
+        return ANA::ZERO;
+    }
+
+    let denominator = target_leverage
+        .checked_mul(price.checked_sub(floor).unwrap())
+        .unwrap()
+        .checked_add(floor)
+        .unwrap();
+
+    let amount = numerator
+        .checked_div(denominator)
+        .unwrap()
+        .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+    ANA::from_decimal(amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leverage() {
+        let floor = Decimal::ONE;
+
+        assert_eq!(leverage(ANA::new(100), NIRV::ZERO, floor), Decimal::ONE);
+        assert_eq!(
+            leverage(ANA::new(100), NIRV::new(50), floor),
+            Decimal::new(2, 0)
+        );
+        assert_eq!(
+            leverage(ANA::new(100), NIRV::new(75), floor),
+            Decimal::new(4, 0)
+        );
+        assert_eq!(leverage(ANA::new(100), NIRV::new(100), floor), Decimal::MAX);
+    }
+
+    #[test]
+    fn test_max_leverage() {
+        assert_eq!(max_leverage(Decimal::ZERO), Decimal::ONE);
+        assert_eq!(max_leverage(Decimal::new(5, 1)), Decimal::new(2, 0));
+        assert_eq!(max_leverage(Decimal::new(75, 2)), Decimal::new(4, 0));
+        assert_eq!(max_leverage(Decimal::ONE), Decimal::MAX);
+    }
+
+    #[test]
+    fn test_effective_price() {
+        let price = Decimal::new(2, 0);
+
+        assert_eq!(effective_price(price, Decimal::ZERO, Decimal::ZERO), price);
+
+        // 2 / (0.8 * 0.5)
+        assert_eq!(
+            effective_price(price, Decimal::new(2, 1), Decimal::new(5, 1)),
+            Decimal::new(5, 0)
+        );
+    }
+
+    #[test]
+    fn test_ana_to_reach_leverage_at_floor() {
+        let floor = Decimal::ONE;
+
+        // buying at the floor doubles the stake for 2x
+        let x = ana_to_reach_leverage(ANA::new(100), NIRV::ZERO, floor, floor, Decimal::new(2, 0));
+        assert_eq!(x, ANA::new(100));
+        assert_eq!(
+            leverage(ANA::new(200), NIRV::new(100), floor),
+            Decimal::new(2, 0)
+        );
+
+        // already there
+        let x = ana_to_reach_leverage(
+            ANA::new(200),
+            NIRV::new(100),
+            floor,
+            floor,
+            Decimal::new(2, 0),
+        );
+        assert_eq!(x, ANA::ZERO);
+    }
+
+    #[test]
+    fn test_ana_to_reach_leverage_above_floor() {
+        let floor = Decimal::ONE;
+        let price = Decimal::new(2, 0);
+
+        // 2(100) - 100 / (1 + 2(2 - 1)) = 33.333333
+        let x = ana_to_reach_leverage(ANA::new(100), NIRV::ZERO, floor, price, Decimal::new(2, 0));
+        assert_eq!(x, ANA::from_u64(33_333_333));
+
+        // the purchase lands on the target, give or take rounding
+        let borrowed = NIRV::from_u64(66_666_666);
+        let l = leverage(ANA::new(100) + x, borrowed, floor);
+        assert_eq!(l.round_dp(4), Decimal::new(2, 0));
+    }
+
+    #[test]
+    fn test_ana_to_reach_leverage_underwater() {
+        let floor = Decimal::ONE;
+
+        let x = ana_to_reach_leverage(
+            ANA::new(100),
+            NIRV::new(100),
+            floor,
+            floor,
+            Decimal::new(2, 0),
+        );
+        assert_eq!(x, ANA::ZERO);
+    }
+}
@@ -1,18 +1,20 @@
 use crate::utils::admin;
 use anchor_lang::prelude::*;
 use anchor_spl::token::{Mint, Token, TokenAccount};
+mod account_migration;
 mod bond_math;
 mod bootstrap_math;
 mod decimal;
 mod errors;
 mod instructions;
+mod leverage_math;
 mod numbers;
 mod price_math;
 mod state;
 mod utils;
 
 use instructions::*;
-use numbers::{CoarseNumber, PreciseNumber, ANA};
+use numbers::{CoarseNumber, PreciseNumber, ANA, NIRV};
 use state::*;
 use utils::is_debug;
 declare_id!("nirkXSE28jCQoK8SmKWqxXz3L9vbSRGKS24iYJj8Aeo");
@@ -92,6 +94,24 @@ pub mod nirvana {
         )
     }
 
+    /// Buy ANA from the AMM and stake it
+    pub fn buy_and_stake(
+        ctx: Context<BuyAndStake>,
+        amount_of_ana: ANA,
+        expected_total_price: u64,
+    ) -> Result<()> {
+        instructions::buy_and_stake::handler(ctx, amount_of_ana, expected_total_price)
+    }
+
+    /// Unstake ANA and sell it to the AMM
+    pub fn unstake_and_sell(
+        ctx: Context<UnstakeAndSell>,
+        amount: u64,
+        expected_total_price: u64,
+    ) -> Result<()> {
+        instructions::unstake_and_sell::handler(ctx, amount, expected_total_price)
+    }
+
     /// Set whether the treasury account can be used for ANA buyback
     pub fn set_treasury_account_is_for_amm(
         ctx: Context<SetTreasuryAccountForAmm>,
@@ -108,6 +128,14 @@ pub mod nirvana {
         instructions::set_treasury_account_for_prana::handler(ctx, is_for_prana)
     }
 
+    /// Set whether the treasury account can be used for trana
+    pub fn set_treasury_account_is_for_trana(
+        ctx: Context<SetTreasuryAccountForTrana>,
+        is_for_trana: bool,
+    ) -> Result<()> {
+        instructions::set_treasury_account_for_trana::handler(ctx, is_for_trana)
+    }
+
     pub fn create_trana_meta(
         ctx: Context<CreateTranaMeta>,
         vesting_length_seconds: u64,
@@ -128,6 +156,11 @@ pub mod nirvana {
         instructions::stake_ana::handler(ctx, amount)
     }
 
+    /// Stake the signer's ANA into another user's position
+    pub fn stake_ana_for(ctx: Context<StakeAnaFor>, amount: u64) -> Result<()> {
+        instructions::stake_ana_for::handler(ctx, amount)
+    }
+
     pub fn unstake_ana(ctx: Context<UnstakeAna>, amount: u64) -> Result<()> {
         instructions::unstake_ana::handler(ctx, amount)
     }
@@ -162,16 +195,184 @@ pub mod nirvana {
         instructions::repay_nirv::handler(ctx, amount)
     }
 
+    /// Start the unstake cooldown, as a fee-free alternative to unstake_ana
+    pub fn request_unstake(ctx: Context<RequestUnstake>, amount: u64) -> Result<()> {
+        instructions::request_unstake::handler(ctx, amount)
+    }
+
+    /// Withdraw the pending ANA once the unstake cooldown is over
+    pub fn complete_unstake(ctx: Context<CompleteUnstake>) -> Result<()> {
+        instructions::complete_unstake::handler(ctx)
+    }
+
+    /// Stake the pending ANA again
+    pub fn cancel_unstake(ctx: Context<CancelUnstake>) -> Result<()> {
+        instructions::cancel_unstake::handler(ctx)
+    }
+
+    /// Create a stream paying a token to ANA stakers
+    pub fn create_reward_stream(
+        ctx: Context<CreateRewardStream>,
+        bump: u8,
+        emission_per_second: u64,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::create_reward_stream::handler(
+            ctx,
+            bump,
+            emission_per_second,
+            start_time,
+            end_time,
+        )
+    }
+
+    /// Add tokens to a reward stream's vault
+    pub fn fund_reward_stream(ctx: Context<FundRewardStream>, amount: u64) -> Result<()> {
+        instructions::fund_reward_stream::handler(ctx, amount)
+    }
+
+    /// Start earning from a reward stream
+    pub fn join_reward_stream(ctx: Context<JoinRewardStream>, bump: u8) -> Result<()> {
+        instructions::join_reward_stream::handler(ctx, bump)
+    }
+
+    /// Claim the tokens earned from a reward stream
+    pub fn claim_stream_rewards(ctx: Context<ClaimStreamRewards>) -> Result<()> {
+        instructions::claim_stream_rewards::handler(ctx)
+    }
+
+    /// Stop tracking an ended reward stream
+    pub fn leave_reward_stream(ctx: Context<LeaveRewardStream>) -> Result<()> {
+        instructions::leave_reward_stream::handler(ctx)
+    }
+
+    /// Take back the tokens an ended reward stream doesn't owe
+    pub fn reclaim_reward_stream(ctx: Context<ReclaimRewardStream>) -> Result<()> {
+        instructions::reclaim_reward_stream::handler(ctx)
+    }
+
+    /// Create the sANA liquid staking vault and its staking position
+    pub fn init_sana_vault(ctx: Context<InitSanaVault>, bump: u8, reward_bump: u8) -> Result<()> {
+        instructions::init_sana_vault::handler(ctx, bump, reward_bump)
+    }
+
+    /// Stake ANA through the vault for sANA
+    pub fn deposit_sana(ctx: Context<DepositSana>, amount: u64) -> Result<()> {
+        instructions::deposit_sana::handler(ctx, amount)
+    }
+
+    /// Burn sANA for its share of the vault's ANA
+    pub fn withdraw_sana(ctx: Context<WithdrawSana>, sana_amount: u64) -> Result<()> {
+        instructions::withdraw_sana::handler(ctx, sana_amount)
+    }
+
+    /// Compound the vault's prANA into its stake
+    pub fn compound_sana_vault(ctx: Context<CompoundSanaVault>) -> Result<()> {
+        instructions::compound_sana_vault::handler(ctx)
+    }
+
+    /// Lock staked ANA for one of the lock tiers to boost its prANA rewards
+    pub fn lock_ana(ctx: Context<LockAna>, amount: u64, duration_seconds: u64) -> Result<()> {
+        instructions::lock_ana::handler(ctx, amount, duration_seconds)
+    }
+
+    /// Relock the locked ANA for one of the lock tiers, starting now
+    pub fn extend_lock(ctx: Context<ExtendLock>, duration_seconds: u64) -> Result<()> {
+        instructions::extend_lock::handler(ctx, duration_seconds)
+    }
+
+    /// Unlock the locked ANA once the lock has ended, callable by anyone
+    pub fn unlock_ana(ctx: Context<UnlockAna>) -> Result<()> {
+        instructions::unlock_ana::handler(ctx)
+    }
+
+    /// Borrow NIRV, buy ANA and stake it in one go, up to the target leverage
+    pub fn lever_up(
+        ctx: Context<LeverUp>,
+        target_leverage: CoarseNumber,
+        max_total_cost: u64,
+    ) -> Result<()> {
+        instructions::lever_up::handler(ctx, target_leverage, max_total_cost)
+    }
+
+    /// Unstake ANA, sell it and repay the NIRV loan
+    pub fn lever_down(ctx: Context<LeverDown>, amount: u64, min_total_proceeds: u64) -> Result<()> {
+        instructions::lever_down::handler(ctx, amount, min_total_proceeds)
+    }
+
+    /// Opt in or out of repaying borrowed NIRV with prANA rewards
+    pub fn set_self_repaying(ctx: Context<SetSelfRepaying>, self_repaying: bool) -> Result<()> {
+        instructions::set_self_repaying::handler(ctx, self_repaying)
+    }
+
+    /// Repay a self-repaying NIRV loan with the user's prANA rewards
+    pub fn self_repay_nirv(ctx: Context<SelfRepayNirv>) -> Result<()> {
+        instructions::self_repay_nirv::handler(ctx)
+    }
+
+    /// Repay part of an undercollateralized NIRV loan for the borrower's staked ANA
+    pub fn liquidate_nirv(ctx: Context<LiquidateNirv>, amount: u64) -> Result<()> {
+        instructions::liquidate_nirv::handler(ctx, amount)
+    }
+
+    /// Emit the health of a user's NIRV loan
+    pub fn get_nirv_health(ctx: Context<GetNirvHealth>) -> Result<()> {
+        instructions::get_nirv_health::handler(ctx)
+    }
+
+    /// Mint NIRV 1:1 for a stablecoin through the peg stability module
+    pub fn psm_mint(ctx: Context<PsmMint>, amount: u64) -> Result<()> {
+        instructions::psm_mint::handler(ctx, amount)
+    }
+
+    /// Redeem NIRV 1:1 for a stablecoin through the peg stability module
+    pub fn psm_redeem(ctx: Context<PsmRedeem>, amount: u64) -> Result<()> {
+        instructions::psm_redeem::handler(ctx, amount)
+    }
+
+    /// Claim prANA rewards, realize them at the floor and stake the ANA
+    pub fn compound(ctx: Context<Compound>) -> Result<()> {
+        instructions::compound::handler(ctx)
+    }
+
+    /// Let a keeper compound rewards on the owner's behalf
+    pub fn set_compound_keeper(ctx: Context<SetCompoundKeeper>, keeper: Pubkey) -> Result<()> {
+        instructions::set_compound_keeper::handler(ctx, keeper)
+    }
+
+    /// Let a delegate claim, compound and restake on the owner's behalf
+    pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+        instructions::set_delegate::handler(ctx, delegate)
+    }
+
     pub fn realize_pre_ana(ctx: Context<RealizePreAna>, amount: u64) -> Result<()> {
         instructions::realize_pre_ana::handler(ctx, amount)
     }
 
+    /// Realize prANA by burning NIRV at the floor price
+    pub fn realize_pre_ana_with_nirv(
+        ctx: Context<RealizePreAnaWithNirv>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::realize_pre_ana_with_nirv::handler(ctx, amount)
+    }
+
+    /// Pause or resume realizing prANA with NIRV
+    pub fn set_realize_with_nirv_paused(
+        ctx: Context<SetRealizeWithNirvPaused>,
+        paused: bool,
+    ) -> Result<()> {
+        instructions::set_realize_with_nirv_paused::handler(ctx, paused)
+    }
+
     pub fn purchase_trana(
         ctx: Context<PurchaseTrana>,
         payment_u64: u64,
         max_offered_price_u64: u64,
+        auto_stake: bool,
     ) -> Result<()> {
-        instructions::purchase_trana::handler(ctx, payment_u64, max_offered_price_u64)
+        instructions::purchase_trana::handler(ctx, payment_u64, max_offered_price_u64, auto_stake)
     }
 
     /// Creates an empty bond account for the user
@@ -187,6 +388,24 @@ pub mod nirvana {
         instructions::redeem_trana::handler(ctx)
     }
 
+    /// Redeem the vested ANA of a trANA bond into a staked position
+    pub fn redeem_trana_and_stake(ctx: Context<RedeemTranaAndStake>) -> Result<()> {
+        instructions::redeem_trana_and_stake::handler(ctx)
+    }
+
+    /// Redeem everything left in a trANA bond now, less the early exit penalty
+    pub fn exit_trana_early(ctx: Context<ExitTranaEarly>) -> Result<()> {
+        instructions::exit_trana_early::handler(ctx)
+    }
+
+    /// Redeem many trANA bonds at once, passed in the remaining accounts
+    pub fn redeem_all_trana<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemAllTrana<'info>>,
+        indices: Vec<u8>,
+    ) -> Result<()> {
+        instructions::redeem_all_trana::handler(ctx, indices)
+    }
+
     /// Burn ANA in exchange for reserve tokens
     pub fn buyback_ana(ctx: Context<BuybackAna>, amount: u64) -> Result<()> {
         instructions::buyback_ana::handler(ctx, amount)
@@ -203,6 +422,25 @@ pub mod nirvana {
         instructions::reward::handler(ctx)
     }
 
+    /// Whitelist a money market for the peg stability module and set its debt ceiling
+    pub fn set_psm_market(
+        ctx: Context<SetPsmMarket>,
+        for_psm: bool,
+        debt_ceiling: NIRV,
+    ) -> Result<()> {
+        instructions::set_psm_market::handler(ctx, for_psm, debt_ceiling)
+    }
+
+    /// Set the peg stability module mint and redeem fees
+    pub fn set_psm_fees(
+        ctx: Context<SetPsmFees>,
+        mint_fee: CoarseNumber,
+        redeem_fee: CoarseNumber,
+    ) -> Result<()> {
+        instructions::set_psm_fees::handler(ctx, mint_fee, redeem_fee)
+    }
+
+    /// Set the rate used by the debug reward, reward_by_time follows the emission schedule
     pub fn set_reward_rate(ctx: Context<SetRewardRate>, reward_rate: PreciseNumber) -> Result<()> {
         instructions::set_reward_rate::handler(ctx, reward_rate)
     }
@@ -226,6 +464,43 @@ pub mod nirvana {
         instructions::set_nirv_debt_fee::handler(ctx, fee)
     }
 
+    /// Set the utilization based interest rates for NIRV debt
+    pub fn set_nirv_interest_rate_model(
+        ctx: Context<SetNirvInterestRateModel>,
+        base_rate: CoarseNumber,
+        rate_slope: CoarseNumber,
+        optimal_utilization: CoarseNumber,
+        jump_rate_slope: CoarseNumber,
+    ) -> Result<()> {
+        instructions::set_nirv_interest_rate_model::handler(
+            ctx,
+            base_rate,
+            rate_slope,
+            optimal_utilization,
+            jump_rate_slope,
+        )
+    }
+
+    /// Rewrite an account laid out before its indices widened or its fields grew
+    ///
+    /// A UserRewardV2 is passed its NirvCenter and config.
+    pub fn migrate_account(ctx: Context<MigrateAccount>) -> Result<()> {
+        instructions::migrate_account::handler(ctx)
+    }
+
+    /// Move a v1 UserReward into the UserRewardV2 and close it
+    pub fn migrate_user_reward(ctx: Context<MigrateUserReward>, bump: u8) -> Result<()> {
+        instructions::migrate_user_reward::handler(ctx, bump)
+    }
+
+    pub fn set_nirv_liquidation_params(
+        ctx: Context<SetNirvLiquidationParams>,
+        close_factor: CoarseNumber,
+        liquidation_bonus: CoarseNumber,
+    ) -> Result<()> {
+        instructions::set_nirv_liquidation_params::handler(ctx, close_factor, liquidation_bonus)
+    }
+
     pub fn set_instant_buy_fee(ctx: Context<SetInstantBuyFee>, fee: CoarseNumber) -> Result<()> {
         instructions::set_instant_buy_fee::handler(ctx, fee)
     }
@@ -242,6 +517,13 @@ pub mod nirvana {
         instructions::set_unstake_fee::handler(ctx, fee)
     }
 
+    pub fn set_unstake_cooldown(
+        ctx: Context<SetUnstakeCooldown>,
+        cooldown_seconds: u64,
+    ) -> Result<()> {
+        instructions::set_unstake_cooldown::handler(ctx, cooldown_seconds)
+    }
+
     pub fn set_bond_bcv(ctx: Context<SetBondBcv>, sensitivity: PreciseNumber) -> Result<()> {
         instructions::set_trana_sensitivity::handler(ctx, sensitivity)
     }
@@ -257,6 +539,39 @@ pub mod nirvana {
         instructions::set_trana_enabled::handler(ctx, is_enabled)
     }
 
+    pub fn set_trana_capacity(
+        ctx: Context<SetTranaCapacity>,
+        capacity: ANA,
+        max_per_purchase: ANA,
+        max_per_user: ANA,
+    ) -> Result<()> {
+        instructions::set_trana_capacity::handler(ctx, capacity, max_per_purchase, max_per_user)
+    }
+
+    pub fn set_trana_vesting(
+        ctx: Context<SetTranaVesting>,
+        cliff_seconds: u64,
+        release_step_seconds: u64,
+        early_exit_enabled: bool,
+        early_exit_penalty: CoarseNumber,
+    ) -> Result<()> {
+        instructions::set_trana_vesting::handler(
+            ctx,
+            cliff_seconds,
+            release_step_seconds,
+            early_exit_enabled,
+            early_exit_penalty,
+        )
+    }
+
+    pub fn set_trana_window(
+        ctx: Context<SetTranaWindow>,
+        open_time: i64,
+        close_time: i64,
+    ) -> Result<()> {
+        instructions::set_trana_window::handler(ctx, open_time, close_time)
+    }
+
     pub fn reward(ctx: Context<Reward>) -> Result<()> {
         instructions::reward::handler(ctx)
     }
@@ -280,7 +595,57 @@ pub mod nirvana {
         instructions::set_debug_mode::handler(ctx, debug_mode)
     }
 
-    /// Public method for issuing rewards
+    /// Create the account publishing the prANA emission schedule
+    pub fn init_emission_schedule(ctx: Context<InitEmissionSchedule>, bump: u8) -> Result<()> {
+        instructions::init_emission_schedule::handler(ctx, bump)
+    }
+
+    /// Publish the next epoch of the emission schedule
+    pub fn add_emission_epoch(
+        ctx: Context<AddEmissionEpoch>,
+        start_time: u64,
+        rate: PreciseNumber,
+        decay: PreciseNumber,
+    ) -> Result<()> {
+        instructions::add_emission_epoch::handler(ctx, start_time, rate, decay)
+    }
+
+    /// Set the bounty paid to whoever calls reward_by_time
+    pub fn set_keeper_bounty(
+        ctx: Context<SetKeeperBounty>,
+        share: CoarseNumber,
+        flat: ANA,
+        cap: ANA,
+    ) -> Result<()> {
+        instructions::set_keeper_bounty::handler(ctx, share, flat, cap)
+    }
+
+    /// Close the history, once the commitment is closed
+    pub fn close_history(ctx: Context<CloseHistory>) -> Result<()> {
+        instructions::close_history::handler(ctx)
+    }
+
+    /// Close a claimed commitment
+    pub fn close_commitment(ctx: Context<CloseCommitment>) -> Result<()> {
+        instructions::close_commitment::handler(ctx)
+    }
+
+    /// Close a fully redeemed trANA contract
+    pub fn close_user_trana_contract(ctx: Context<CloseUserTranaContract>) -> Result<()> {
+        instructions::close_user_trana_contract::handler(ctx)
+    }
+
+    /// Close an empty reward position
+    pub fn close_user_reward(ctx: Context<CloseUserReward>) -> Result<()> {
+        instructions::close_user_reward::handler(ctx)
+    }
+
+    /// Close an empty fee collector
+    pub fn close_fee_collector(ctx: Context<CloseFeeCollector>) -> Result<()> {
+        instructions::close_fee_collector::handler(ctx)
+    }
+
+    /// Public method for issuing rewards, the caller earns the keeper bounty
     pub fn reward_by_time(ctx: Context<RewardByTime>) -> Result<()> {
         instructions::reward_by_time::handler(ctx)
     }
@@ -1,3 +1,4 @@
+use crate::errors::ErrorCode;
 use anchor_lang::prelude::*;
 use std::{
     convert::TryInto,
@@ -20,6 +21,16 @@ pub struct PreciseNumber {
     pub val: u64,
 }
 
+/// 128-bit fixed point for reward and fee indices, which only ever grow
+///
+/// Adding and subtracting use the whole u128, but a Decimal only has a 96-bit
+/// mantissa, so at 18 decimals only indices up to about 7.9e10 convert to or
+/// from one. Those conversions are checked rather than assumed.
+#[derive(Default, Clone, Copy, Debug, AnchorDeserialize, AnchorSerialize, PartialEq)]
+pub struct IndexNumber {
+    pub val: u128,
+}
+
 #[derive(Default, Clone, Copy, Debug, AnchorDeserialize, AnchorSerialize)]
 pub struct CoarseNumber {
     pub val: u64,
@@ -149,6 +160,54 @@ impl PreciseNumber {
     }
 }
 
+impl IndexNumber {
+    pub const SCALE: u32 = 18;
+    pub const ZERO: IndexNumber = IndexNumber { val: 0 };
+
+    pub fn checked_from_decimal(d: Decimal) -> Result<IndexNumber> {
+        let mut d = d.to_owned();
+        d.rescale(IndexNumber::SCALE);
+
+        // rescale gives up on precision rather than overflow
+        if d.scale() != IndexNumber::SCALE {
+            return Err(ErrorCode::IndexOutOfDecimalRange.into());
+        }
+
+        Ok(IndexNumber {
+            val: d
+                .mantissa()
+                .try_into()
+                .map_err(|_| ErrorCode::IndexOutOfDecimalRange)?,
+        })
+    }
+
+    pub fn checked_to_decimal(&self) -> Result<Decimal> {
+        let val = self
+            .val
+            .try_into()
+            .map_err(|_| ErrorCode::IndexOutOfDecimalRange)?;
+
+        Decimal::try_from_i128_with_scale(val, IndexNumber::SCALE)
+            .map_err(|_| ErrorCode::IndexOutOfDecimalRange.into())
+    }
+
+    pub fn new(n: u64) -> IndexNumber {
+        IndexNumber {
+            val: u128::from(n)
+                .checked_mul(10u128.pow(IndexNumber::SCALE))
+                .unwrap(),
+        }
+    }
+
+    /// Widen an index kept as a PreciseNumber
+    pub fn from_precise(n: PreciseNumber) -> IndexNumber {
+        let widen = 10u128.pow(IndexNumber::SCALE - PreciseNumber::SCALE);
+        IndexNumber {
+            val: u128::from(n.val).checked_mul(widen).unwrap(),
+        }
+    }
+}
+
 impl CoarseNumber {
     pub const SCALE: u32 = 6;
     pub const DENOMINATOR: u64 = 10u64.pow(CoarseNumber::SCALE);
@@ -337,6 +396,15 @@ impl Sub<PreciseNumber> for PreciseNumber {
     }
 }
 
+impl Sub<IndexNumber> for IndexNumber {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self::Output {
+        Self {
+            val: self.val.checked_sub(other.val).unwrap(),
+        }
+    }
+}
+
 impl Add<ANA> for ANA {
     type Output = Self;
     fn add(self, other: Self) -> Self::Output {
@@ -395,6 +463,14 @@ impl AddAssign<PreciseNumber> for PreciseNumber {
     }
 }
 
+impl AddAssign<IndexNumber> for IndexNumber {
+    fn add_assign(&mut self, other: Self) {
+        *self = Self {
+            val: self.val.checked_add(other.val).unwrap(),
+        }
+    }
+}
+
 impl SubAssign<ANA> for ANA {
     fn sub_assign(&mut self, other: Self) {
         *self = Self {
@@ -420,7 +496,6 @@ impl SubAssign<ALMS> for ALMS {
 }
 
 #[cfg(test)]
-
 mod tests {
     use super::*;
 
@@ -451,4 +526,49 @@ mod tests {
         a -= ANA { val: 100 };
         assert_eq!(a.val, 0);
     }
+
+    #[test]
+    fn index_past_u64() {
+        // 20M is past what a u64 holds at 12 decimals
+        let i = IndexNumber::new(20_000_000);
+        assert_eq!(i.val, 20_000_000_000_000_000_000_000_000);
+        let d = i.checked_to_decimal().unwrap();
+        assert_eq!(d, Decimal::new(20_000_000, 0));
+        assert_eq!(IndexNumber::checked_from_decimal(d).unwrap(), i);
+
+        // The smallest step still round trips
+        let step = IndexNumber { val: 1 };
+        let mut i = i;
+        i += step;
+        let d = i.checked_to_decimal().unwrap();
+        assert_eq!(IndexNumber::checked_from_decimal(d).unwrap(), i);
+        assert_eq!(i - IndexNumber::new(20_000_000), step);
+    }
+
+    #[test]
+    fn index_past_decimal() {
+        // A Decimal's 96-bit mantissa holds about 7.9e10 at 18 decimals
+        let i = IndexNumber::new(79_000_000_000);
+        assert!(i.checked_to_decimal().is_ok());
+
+        let i = IndexNumber::new(80_000_000_000);
+        assert!(i.checked_to_decimal().is_err());
+        assert!(IndexNumber::checked_from_decimal(Decimal::new(80_000_000_000, 0)).is_err());
+
+        // but adding and subtracting still work on the whole u128
+        let step = IndexNumber::new(1);
+        let mut j = i;
+        j += step;
+        assert_eq!(j - i, step);
+    }
+
+    #[test]
+    fn index_from_precise() {
+        let p = PreciseNumber {
+            val: 1_234_567_890_123,
+        };
+        let i = IndexNumber::from_precise(p);
+        assert_eq!(i.val, 1_234_567_890_123_000_000);
+        assert_eq!(i.checked_to_decimal().unwrap(), p.to_decimal());
+    }
 }
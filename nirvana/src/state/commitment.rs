@@ -42,6 +42,11 @@ impl Commitment {
         self.reward_index = PreciseNumber::from_decimal(new_ratio);
     }
 
+    /// Can the account be closed? Nothing escrowed, or the reward claimed
+    pub fn can_close(&self) -> bool {
+        self.claimed_and_dead || self.target_spend_usd == 0
+    }
+
     pub fn escrow_amount(&self) -> u64 {
         self.target_spend_usd / 100
     }
@@ -148,4 +153,16 @@ mod test {
 
         assert_eq!(amount, ANA { val: 10_000_000 });
     }
+
+    #[test]
+    fn test_can_close() {
+        let mut c = Commitment::default();
+        assert!(c.can_close());
+
+        c.target_spend_usd = 100;
+        assert!(!c.can_close());
+
+        c.claimed_and_dead = true;
+        assert!(c.can_close());
+    }
 }
@@ -0,0 +1,227 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{Decimalable, PreciseNumber};
+use anchor_lang::prelude::*;
+use rust_decimal::prelude::*;
+use std::convert::TryInto;
+
+/// Most epochs an emission schedule can publish
+pub const MAX_EMISSION_EPOCHS: usize = 8;
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+#[derive(Default, Debug, Clone, Copy, AnchorDeserialize, AnchorSerialize)]
+pub struct EmissionEpoch {
+    /// When the epoch starts, it runs until the next one starts
+    pub start_time: u64,
+
+    /// Daily prANA reward rate on the ANA supply at the start of the epoch
+    pub rate: PreciseNumber,
+
+    /// Share of the rate cut at the end of every day of the epoch
+    pub decay: PreciseNumber,
+}
+
+impl EmissionEpoch {
+    /// Daily rate on the given day of the epoch
+    fn rate_on_day(&self, day: u64) -> Decimal {
+        let kept = Decimal::ONE.checked_sub(self.decay.to_decimal()).unwrap();
+
+        self.rate
+            .to_decimal()
+            .checked_mul(kept.checked_powu(day).unwrap())
+            .unwrap()
+            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero)
+    }
+
+    /// Emission between two offsets into the epoch, in seconds
+    fn emission_between(&self, start: u64, end: u64) -> Decimal {
+        let seconds_in_day = Decimal::new(SECONDS_IN_DAY.try_into().unwrap(), 0);
+        let mut emission = Decimal::ZERO;
+        let mut t = start;
+
+        // Whole days share the same rate
+        while t < end {
+            let day = t / SECONDS_IN_DAY;
+            let day_end = std::cmp::min(end, (day + 1) * SECONDS_IN_DAY);
+            let seconds = Decimal::new((day_end - t).try_into().unwrap(), 0);
+
+            emission = emission
+                .checked_add(
+                    self.rate_on_day(day)
+                        .checked_mul(seconds)
+                        .unwrap()
+                        .checked_div(seconds_in_day)
+                        .unwrap(),
+                )
+                .unwrap();
+            t = day_end;
+        }
+
+        emission
+    }
+}
+
+/// Published prANA emission schedule
+///
+/// Epochs are only ever appended in the future, so the rate for any past
+/// or running epoch can't be changed once published.
+#[account]
+#[derive(Default, Debug)]
+pub struct EmissionSchedule {
+    /// Link to NirvCenter
+    pub nirv_center: Pubkey,
+
+    /// Epochs in order of start time
+    pub epochs: [EmissionEpoch; MAX_EMISSION_EPOCHS],
+
+    /// How many of the epochs are in use
+    pub epoch_count: u8,
+
+    pub bump: u8,
+}
+
+impl EmissionSchedule {
+    fn published(&self) -> &[EmissionEpoch] {
+        &self.epochs[..self.epoch_count as usize]
+    }
+
+    /// Publish a new epoch after the others
+    pub fn add_epoch(&mut self, now: u64, epoch: EmissionEpoch) -> Result<()> {
+        if self.epoch_count as usize >= MAX_EMISSION_EPOCHS {
+            return Err(ErrorCode::EmissionScheduleFull.into());
+        }
+
+        let after = self
+            .published()
+            .last()
+            .map_or(now, |last| std::cmp::max(now, last.start_time));
+
+        if epoch.start_time <= after {
+            return Err(ErrorCode::InvalidEmissionEpochStart.into());
+        }
+
+        if epoch.decay.to_decimal() >= Decimal::ONE {
+            return Err(ErrorCode::InvalidEmissionDecay.into());
+        }
+
+        self.epochs[self.epoch_count as usize] = epoch;
+        self.epoch_count += 1;
+
+        Ok(())
+    }
+
+    /// Daily rate in effect at a time, zero before the first epoch
+    pub fn rate_at(&self, time: u64) -> Decimal {
+        self.published()
+            .iter()
+            .rev()
+            .find(|epoch| epoch.start_time <= time)
+            .map_or(Decimal::ZERO, |epoch| {
+                epoch.rate_on_day((time - epoch.start_time) / SECONDS_IN_DAY)
+            })
+    }
+
+    /// Emission between two times, split across the epochs they span
+    ///
+    /// This is the daily rate times the days it ran for, summed up.
+    pub fn emission_between(&self, start: u64, end: u64) -> Decimal {
+        let epochs = self.published();
+        let mut emission = Decimal::ZERO;
+
+        for (i, epoch) in epochs.iter().enumerate() {
+            let epoch_end = epochs.get(i + 1).map_or(u64::MAX, |next| next.start_time);
+            let from = std::cmp::max(start, epoch.start_time);
+            let to = std::cmp::min(end, epoch_end);
+
+            if from >= to {
+                continue;
+            }
+
+            emission = emission
+                .checked_add(epoch.emission_between(from - epoch.start_time, to - epoch.start_time))
+                .unwrap();
+        }
+
+        emission
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY: u64 = SECONDS_IN_DAY;
+
+    fn epoch(start_time: u64, rate_percent: u64, decay_percent: u64) -> EmissionEpoch {
+        EmissionEpoch {
+            start_time,
+            rate: PreciseNumber::from_decimal(Decimal::new(rate_percent.try_into().unwrap(), 2)),
+            decay: PreciseNumber::from_decimal(Decimal::new(decay_percent.try_into().unwrap(), 2)),
+        }
+    }
+
+    #[test]
+    fn add_epoch_only_in_the_future() {
+        let mut schedule = EmissionSchedule::default();
+
+        assert!(schedule.add_epoch(100, epoch(100, 1, 0)).is_err());
+        schedule.add_epoch(100, epoch(200, 1, 0)).unwrap();
+        assert!(schedule.add_epoch(100, epoch(150, 1, 0)).is_err());
+        assert!(schedule.add_epoch(100, epoch(300, 1, 100)).is_err());
+        schedule.add_epoch(100, epoch(300, 1, 0)).unwrap();
+        assert_eq!(schedule.epoch_count, 2);
+
+        for i in 2..MAX_EMISSION_EPOCHS as u64 {
+            schedule.add_epoch(100, epoch(300 + i, 1, 0)).unwrap();
+        }
+        assert!(schedule.add_epoch(100, epoch(1_000, 1, 0)).is_err());
+    }
+
+    #[test]
+    fn rate_at() {
+        let mut schedule = EmissionSchedule::default();
+        schedule.add_epoch(0, epoch(DAY, 4, 50)).unwrap();
+        schedule.add_epoch(0, epoch(10 * DAY, 1, 0)).unwrap();
+
+        assert_eq!(schedule.rate_at(0), Decimal::ZERO);
+        assert_eq!(schedule.rate_at(DAY), Decimal::new(4, 2));
+        assert_eq!(schedule.rate_at(2 * DAY + 1), Decimal::new(2, 2));
+        assert_eq!(schedule.rate_at(3 * DAY), Decimal::new(1, 2));
+        assert_eq!(schedule.rate_at(10 * DAY), Decimal::new(1, 2));
+        assert_eq!(schedule.rate_at(100 * DAY), Decimal::new(1, 2));
+    }
+
+    #[test]
+    fn emission_splits_across_epochs() {
+        let mut schedule = EmissionSchedule::default();
+        schedule.add_epoch(0, epoch(DAY, 4, 0)).unwrap();
+        schedule.add_epoch(0, epoch(2 * DAY, 2, 0)).unwrap();
+
+        // half a day before the schedule starts, half a day at 4%
+        assert_eq!(
+            schedule.emission_between(DAY / 2, DAY + DAY / 2),
+            Decimal::new(2, 2)
+        );
+
+        // half a day at 4%, half a day at 2%
+        assert_eq!(
+            schedule.emission_between(DAY + DAY / 2, 2 * DAY + DAY / 2),
+            Decimal::new(3, 2)
+        );
+    }
+
+    #[test]
+    fn emission_with_decay() {
+        let mut schedule = EmissionSchedule::default();
+        schedule.add_epoch(0, epoch(DAY, 4, 50)).unwrap();
+
+        // 4% then 2% then 1%
+        assert_eq!(schedule.emission_between(DAY, 4 * DAY), Decimal::new(7, 2));
+
+        // half of the 2% day and half of the 1% day
+        assert_eq!(
+            schedule.emission_between(2 * DAY + DAY / 2, 3 * DAY + DAY / 2),
+            Decimal::new(15, 3)
+        );
+    }
+}
@@ -1,13 +1,13 @@
-use crate::numbers::{PreciseNumber, ALMS, ANA, NIRV};
+use crate::numbers::{IndexNumber, ALMS, ANA, NIRV};
 use anchor_lang::prelude::*;
 
 #[account]
 #[derive(Default, Debug)]
 pub struct FeeCollector {
     /// The user's fee indexes
-    pub nirv_fee_index: PreciseNumber,
-    pub ana_fee_index: PreciseNumber,
-    pub prana_fee_index: PreciseNumber,
+    pub nirv_fee_index: IndexNumber,
+    pub ana_fee_index: IndexNumber,
+    pub prana_fee_index: IndexNumber,
 
     /// User account that "owns" this
     pub owner: Pubkey,
@@ -35,4 +35,12 @@ impl FeeCollector {
     pub fn unstake_alms(&mut self, amount: ALMS) {
         self.staked_alms -= amount;
     }
+
+    /// Nothing staked and nothing left to claim
+    pub fn is_empty(&self) -> bool {
+        self.staked_alms == ALMS::ZERO
+            && self.staged_ana == ANA::ZERO
+            && self.staged_nirv == NIRV::ZERO
+            && self.staged_pre_ana == ANA::ZERO
+    }
 }
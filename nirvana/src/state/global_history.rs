@@ -28,6 +28,13 @@ pub struct GlobalHistory {
     pub nirv_repaid: NIRV,
 
     pub bump: u8,
+
+    /// Total prANA paid to keepers for dropping rewards
+    pub keeper_bounties_paid: ANA,
+    /// How many reward drops keepers were paid for
+    pub keeper_bounty_count: u64,
+    /// NIRV debt written off with no staked ANA left to seize
+    pub nirv_bad_debt: NIRV,
 }
 
 impl GlobalHistory {
@@ -46,6 +53,12 @@ impl GlobalHistory {
         self.volume_usd += round_dollars;
         self.net_purchased_ana -= amount_ana;
     }
+
+    pub fn pay_keeper_bounty(&mut self, bounty: ANA) {
+        self.prana_minted += bounty;
+        self.keeper_bounties_paid += bounty;
+        self.keeper_bounty_count += 1;
+    }
 }
 
 #[cfg(test)]
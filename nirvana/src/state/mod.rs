@@ -1,5 +1,6 @@
 pub mod commitment;
 pub mod commitment_meta;
+pub mod emission_schedule;
 pub mod fee_collector;
 pub mod fee_config;
 pub mod global_history;
@@ -10,13 +11,17 @@ pub mod nirv_center_config;
 pub mod nirv_center_config_v3;
 pub mod price_field_v1;
 pub mod price_field_v2;
+pub mod reward_stream;
+pub mod sana_vault;
 pub mod trana_meta;
 pub mod user_reward;
 pub mod user_reward_v2;
 pub mod user_trana_contract;
+pub mod user_trana_purchases;
 
 pub use commitment::*;
 pub use commitment_meta::*;
+pub use emission_schedule::*;
 pub use fee_collector::*;
 pub use fee_config::*;
 pub use global_history::*;
@@ -27,7 +32,10 @@ pub use nirv_center_config::*;
 pub use nirv_center_config_v3::*;
 pub use price_field_v1::*;
 pub use price_field_v2::*;
+pub use reward_stream::*;
+pub use sana_vault::*;
 pub use trana_meta::*;
 pub use user_reward::*;
 pub use user_reward_v2::*;
 pub use user_trana_contract::*;
+pub use user_trana_purchases::*;
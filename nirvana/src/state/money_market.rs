@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use rust_decimal::prelude::*;
+use std::convert::TryInto;
 
-use crate::numbers::{CoarseNumber, Decimalable};
+use crate::errors::ErrorCode;
+use crate::numbers::{CoarseNumber, Decimalable, NIRV};
 
 #[account]
 #[derive(Default)]
@@ -40,10 +42,118 @@ pub struct MoneyMarket {
     pub token_account: Pubkey,
 
     pub bump: u8,
+
+    /// Can this token mint and redeem NIRV at par through the peg stability module?
+    pub for_psm: bool,
+
+    /// Most NIRV that can be outstanding against this token through the peg stability module
+    pub psm_debt_ceiling: NIRV,
+
+    /// NIRV outstanding against this token through the peg stability module
+    pub psm_minted: NIRV,
 }
 
 impl MoneyMarket {
     pub fn rfv_coefficient_into_decimal(&self) -> Decimal {
         self.risk_free_value_coefficient.to_decimal()
     }
+
+    /// Value an amount of the underlying token as NIRV at par
+    ///
+    /// Since tokens out, round down
+    pub fn nirv_for_amount(&self, amount: u64) -> NIRV {
+        let amount = Decimal::new(amount.try_into().unwrap(), self.decimals.into())
+            .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::ToZero);
+
+        NIRV::from_decimal(amount)
+    }
+
+    /// Value an amount of NIRV in the underlying token at par
+    ///
+    /// Since tokens out, round down
+    pub fn amount_for_nirv(&self, amount: NIRV) -> u64 {
+        let mut amount = amount
+            .to_decimal()
+            .round_dp_with_strategy(self.decimals.into(), RoundingStrategy::ToZero);
+        amount.rescale(self.decimals.into());
+
+        amount.mantissa().to_u64().unwrap()
+    }
+
+    /// Record NIRV minted against this token, up to the debt ceiling
+    pub fn psm_mint(&mut self, amount: NIRV) -> Result<()> {
+        let minted = self.psm_minted.val.checked_add(amount.val).unwrap();
+
+        if minted > self.psm_debt_ceiling.val {
+            return Err(ErrorCode::PsmDebtCeilingExceeded.into());
+        }
+
+        self.psm_minted = NIRV::from_u64(minted);
+
+        Ok(())
+    }
+
+    /// Record NIRV redeemed for this token
+    pub fn psm_redeem(&mut self, amount: NIRV) -> Result<()> {
+        if amount.val > self.psm_minted.val {
+            return Err(ErrorCode::PsmInsufficientReserves.into());
+        }
+
+        self.psm_minted -= amount;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn converts_at_par() {
+        let usdc = MoneyMarket {
+            decimals: 6,
+            ..Default::default()
+        };
+        assert_eq!(usdc.nirv_for_amount(1_500_000), NIRV::from_u64(1_500_000));
+        assert_eq!(usdc.amount_for_nirv(NIRV::from_u64(1_500_000)), 1_500_000);
+
+        let dai = MoneyMarket {
+            decimals: 9,
+            ..Default::default()
+        };
+        // dust below NIRV precision is not minted
+        assert_eq!(
+            dai.nirv_for_amount(1_500_000_999),
+            NIRV::from_u64(1_500_000)
+        );
+        assert_eq!(
+            dai.amount_for_nirv(NIRV::from_u64(1_500_000)),
+            1_500_000_000
+        );
+
+        let two_decimals = MoneyMarket {
+            decimals: 2,
+            ..Default::default()
+        };
+        assert_eq!(two_decimals.nirv_for_amount(150), NIRV::from_u64(1_500_000));
+        assert_eq!(two_decimals.amount_for_nirv(NIRV::from_u64(1_509_999)), 150);
+    }
+
+    #[test]
+    fn psm_debt_ceiling() {
+        let mut market = MoneyMarket {
+            psm_debt_ceiling: NIRV::new(100),
+            ..Default::default()
+        };
+
+        market.psm_mint(NIRV::new(60)).unwrap();
+        assert!(market.psm_mint(NIRV::new(41)).is_err());
+        market.psm_mint(NIRV::new(40)).unwrap();
+        assert_eq!(market.psm_minted, NIRV::new(100));
+
+        market.psm_redeem(NIRV::new(30)).unwrap();
+        assert_eq!(market.psm_minted, NIRV::new(70));
+        assert!(market.psm_redeem(NIRV::new(71)).is_err());
+    }
 }
@@ -1,8 +1,13 @@
 use crate::bootstrap_math::BootstrapParams;
-use crate::numbers::{CoarseNumber, Decimalable, PreciseNumber, ALMS, ANA, NIRV};
+use crate::numbers::{CoarseNumber, Decimalable, IndexNumber, PreciseNumber, ALMS, ANA, NIRV};
 use anchor_lang::prelude::*;
 use rust_decimal::prelude::*;
 
+pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60;
+
+/// Shortest unstake cooldown the admin can set, so the unstake fee can't be skipped
+pub const MIN_UNSTAKE_COOLDOWN_SECONDS: u64 = 24 * 60 * 60;
+
 #[account]
 #[derive(Default, Debug)]
 pub struct NirvCenterConfigV3 {
@@ -36,7 +41,7 @@ pub struct NirvCenterConfigV3 {
     /// This is used to calculate the fee distribution among
     /// ALMS staked the same way PRANA rewards are distributed
     /// among ANA stakers.
-    pub nirv_fee_index: PreciseNumber,
+    pub nirv_fee_index: IndexNumber,
 
     /// ANA fee index
     ///
@@ -44,7 +49,7 @@ pub struct NirvCenterConfigV3 {
     /// This is used to calculate the fee distribution among
     /// ALMS staked the same way PRANA rewards are distributed
     /// among ANA stakers.
-    pub ana_fee_index: PreciseNumber,
+    pub ana_fee_index: IndexNumber,
 
     /// PRANA fee index
     ///
@@ -52,7 +57,7 @@ pub struct NirvCenterConfigV3 {
     /// This is used to calculate the fee distribution among
     /// ALMS staked the same way PRANA rewards are distributed
     /// among ANA stakers.
-    pub prana_fee_index: PreciseNumber,
+    pub prana_fee_index: IndexNumber,
 
     /// PRANA reward index
     ///
@@ -73,7 +78,7 @@ pub struct NirvCenterConfigV3 {
     /// the rewards distributed since the user initially staked.
     ///
     /// prANA rewards = ANA staked * (current index - user stake index )
-    pub reward_index: PreciseNumber,
+    pub reward_index: IndexNumber,
 
     /// Token Account for staking ANA
     pub stake_pool_ana: Pubkey,
@@ -87,6 +92,7 @@ pub struct NirvCenterConfigV3 {
     /// to calculate the amount of PRANA to be minted as staking rewards
     /// This value connected with the prana_reward_interval_seconds computes the
     /// total amount of PRANA created per time unit
+    /// reward_by_time keeps it at the emission schedule's active rate
     pub prana_reward_rate: PreciseNumber,
 
     /// PRANA reward interval seconds
@@ -145,6 +151,82 @@ pub struct NirvCenterConfigV3 {
     pub price_curve: Pubkey,
 
     pub bump: u8,
+
+    /// NIRV borrow index
+    ///
+    /// Tracks the cumulative interest accrued per each NIRV borrowed.
+    /// The index starts at one and compounds every time interest accrues.
+    ///
+    /// index = index * (1 + borrow rate * seconds elapsed / seconds per year)
+    ///
+    /// Each borrower records the index when their debt is last updated,
+    /// so the debt owed at any later time is
+    ///
+    /// NIRV debt = borrowed NIRV * (current index / user borrow index)
+    pub nirv_borrow_index: PreciseNumber,
+
+    /// Timestamp of the most recent NIRV interest accrual
+    pub time_of_last_nirv_accrual: u64,
+
+    /// Total NIRV owed by all borrowers, including accrued interest
+    pub total_borrowed_nirv: NIRV,
+
+    /// Yearly NIRV borrow rate at zero utilization
+    pub nirv_base_rate: CoarseNumber,
+
+    /// Yearly NIRV borrow rate added between zero and optimal utilization
+    pub nirv_rate_slope: CoarseNumber,
+
+    /// Utilization after which the jump rate slope applies
+    pub nirv_optimal_utilization: CoarseNumber,
+
+    /// Yearly NIRV borrow rate added between optimal and full utilization
+    pub nirv_jump_rate_slope: CoarseNumber,
+
+    /// NIRV liquidation close factor
+    ///
+    /// The largest share of an undercollateralized borrower's debt
+    /// that can be repaid in a single liquidation
+    pub nirv_close_factor: CoarseNumber,
+
+    /// NIRV liquidation bonus
+    ///
+    /// The extra staked ANA, valued at the floor, paid to a liquidator
+    /// on top of the NIRV they repay. 5% means repaying 100 NIRV
+    /// seizes 105 NIRV worth of ANA.
+    pub nirv_liquidation_bonus: CoarseNumber,
+
+    /// Fee on NIRV minted through the peg stability module
+    pub psm_mint_fee: CoarseNumber,
+
+    /// Fee on NIRV redeemed through the peg stability module
+    pub psm_redeem_fee: CoarseNumber,
+
+    /// Extra reward weight from all locked ANA
+    ///
+    /// Locked ANA earns rewards as if the stake were larger,
+    /// so this is added to the total staked when rewards are dropped.
+    pub total_lock_boost: ANA,
+
+    /// How long requested unstakes wait before they can be withdrawn fee-free
+    pub unstake_cooldown_seconds: u64,
+
+    /// ANA waiting out the unstake cooldown
+    ///
+    /// It is still in the stake pool but earns no rewards.
+    pub total_pending_unstake: ANA,
+
+    /// Share of each reward drop paid to whoever calls reward_by_time
+    pub keeper_bounty_share: CoarseNumber,
+
+    /// Flat prANA paid to whoever calls reward_by_time
+    pub keeper_bounty_flat: ANA,
+
+    /// Most prANA paid to the keeper for one call
+    pub keeper_bounty_cap: ANA,
+
+    /// Stops prANA being realized with NIRV while set
+    pub realize_with_nirv_paused: bool,
 }
 
 impl NirvCenterConfigV3 {
@@ -176,17 +258,19 @@ impl NirvCenterConfigV3 {
         }
     }
 
-    pub fn collect_ana_fee(&mut self, fee_amount: ANA, total_alms_staked: ALMS) {
+    pub fn collect_ana_fee(&mut self, fee_amount: ANA, total_alms_staked: ALMS) -> Result<()> {
         if total_alms_staked == ALMS::ZERO {
-            return;
+            return Ok(());
         }
 
         let index_increase = fee_amount
             .to_decimal()
             .checked_div(total_alms_staked.into())
             .unwrap()
-            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero);
-        self.ana_fee_index += PreciseNumber::from_decimal(index_increase);
+            .round_dp_with_strategy(IndexNumber::SCALE, RoundingStrategy::ToZero);
+        self.ana_fee_index += IndexNumber::checked_from_decimal(index_increase)?;
+
+        Ok(())
     }
 
     pub fn collect_ana_swap_fee(
@@ -194,7 +278,7 @@ impl NirvCenterConfigV3 {
         is_buy: bool,
         swap_amount: ANA,
         total_alms_staked: ALMS,
-    ) -> (ANA, ANA) {
+    ) -> Result<(ANA, ANA)> {
         let fee_rate = if is_buy {
             self.instant_buy_fee.to_decimal()
         } else {
@@ -207,16 +291,16 @@ impl NirvCenterConfigV3 {
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
         let amount_less_fee = swap_amount.to_decimal().checked_sub(fee).unwrap();
 
-        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked);
+        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked)?;
 
-        (ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee))
+        Ok((ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee)))
     }
 
     pub fn collect_trana_buy_fee(
         &mut self,
         buy_amount: ANA,
         total_alms_staked: ALMS,
-    ) -> (ANA, ANA) {
+    ) -> Result<(ANA, ANA)> {
         let fee_rate = self.trana_buy_fee.to_decimal();
         let fee = buy_amount
             .to_decimal()
@@ -225,16 +309,16 @@ impl NirvCenterConfigV3 {
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
         let amount_less_fee = buy_amount.to_decimal().checked_sub(fee).unwrap();
 
-        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked);
+        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked)?;
 
-        (ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee))
+        Ok((ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee)))
     }
 
     pub fn collect_ana_unstake_fee(
         &mut self,
         unstake_amount: ANA,
         total_alms_staked: ALMS,
-    ) -> (ANA, ANA) {
+    ) -> Result<(ANA, ANA)> {
         let unstake_fee = self.unstake_fee.to_decimal();
         let fee = unstake_amount
             .to_decimal()
@@ -243,29 +327,55 @@ impl NirvCenterConfigV3 {
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
         let amount_less_fee = unstake_amount.to_decimal().checked_sub(fee).unwrap();
 
-        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked);
+        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked)?;
+
+        Ok((ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee)))
+    }
+
+    /// Unstake and sell fees charged together on the ANA unstaked
+    pub fn collect_ana_unstake_and_sell_fee(
+        &mut self,
+        unstake_amount: ANA,
+        total_alms_staked: ALMS,
+    ) -> Result<(ANA, ANA)> {
+        let fee_rate = self
+            .unstake_fee
+            .to_decimal()
+            .checked_add(self.sell_fee.to_decimal())
+            .unwrap()
+            .min(Decimal::ONE);
+        let fee = unstake_amount
+            .to_decimal()
+            .checked_mul(fee_rate)
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+        let amount_less_fee = unstake_amount.to_decimal().checked_sub(fee).unwrap();
+
+        self.collect_ana_fee(ANA::from_decimal(fee), total_alms_staked)?;
 
-        (ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee))
+        Ok((ANA::from_decimal(amount_less_fee), ANA::from_decimal(fee)))
     }
 
-    pub fn collect_nirv_fee(&mut self, fee_amount: NIRV, total_alms_staked: ALMS) {
+    pub fn collect_nirv_fee(&mut self, fee_amount: NIRV, total_alms_staked: ALMS) -> Result<()> {
         if total_alms_staked == ALMS::ZERO {
-            return;
+            return Ok(());
         }
 
         let index_increase = fee_amount
             .to_decimal()
             .checked_div(total_alms_staked.into())
             .unwrap()
-            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero);
-        self.nirv_fee_index += PreciseNumber::from_decimal(index_increase);
+            .round_dp_with_strategy(IndexNumber::SCALE, RoundingStrategy::ToZero);
+        self.nirv_fee_index += IndexNumber::checked_from_decimal(index_increase)?;
+
+        Ok(())
     }
 
     pub fn collect_nirv_origination_fee(
         &mut self,
         requested_amount: NIRV,
         total_alms_staked: ALMS,
-    ) -> (NIRV, NIRV) {
+    ) -> Result<(NIRV, NIRV)> {
         let origination_fee = self.nirv_loan_origination_fee.to_decimal();
         let fee = requested_amount
             .to_decimal()
@@ -274,22 +384,47 @@ impl NirvCenterConfigV3 {
             .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::ToZero);
         let amount_less_fee = requested_amount.to_decimal().checked_sub(fee).unwrap();
 
-        self.collect_nirv_fee(NIRV::from_decimal(fee), total_alms_staked);
+        self.collect_nirv_fee(NIRV::from_decimal(fee), total_alms_staked)?;
+
+        Ok((NIRV::from_decimal(amount_less_fee), NIRV::from_decimal(fee)))
+    }
+
+    pub fn collect_psm_fee(
+        &mut self,
+        is_mint: bool,
+        amount: NIRV,
+        total_alms_staked: ALMS,
+    ) -> Result<(NIRV, NIRV)> {
+        let fee_rate = if is_mint {
+            self.psm_mint_fee.to_decimal()
+        } else {
+            self.psm_redeem_fee.to_decimal()
+        };
+        let fee = amount
+            .to_decimal()
+            .checked_mul(fee_rate)
+            .unwrap()
+            .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::ToZero);
+        let amount_less_fee = amount.to_decimal().checked_sub(fee).unwrap();
+
+        self.collect_nirv_fee(NIRV::from_decimal(fee), total_alms_staked)?;
 
-        (NIRV::from_decimal(amount_less_fee), NIRV::from_decimal(fee))
+        Ok((NIRV::from_decimal(amount_less_fee), NIRV::from_decimal(fee)))
     }
 
-    pub fn collect_prana_fee(&mut self, fee_amount: ANA, total_alms_staked: ALMS) {
+    pub fn collect_prana_fee(&mut self, fee_amount: ANA, total_alms_staked: ALMS) -> Result<()> {
         if total_alms_staked == ALMS::ZERO {
-            return;
+            return Ok(());
         }
 
         let index_increase = fee_amount
             .to_decimal()
             .checked_div(total_alms_staked.into())
             .unwrap()
-            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero);
-        self.prana_fee_index += PreciseNumber::from_decimal(index_increase);
+            .round_dp_with_strategy(IndexNumber::SCALE, RoundingStrategy::ToZero);
+        self.prana_fee_index += IndexNumber::checked_from_decimal(index_increase)?;
+
+        Ok(())
     }
 
     /// Calculate a fee from a user's PRANA rewards.
@@ -315,12 +450,153 @@ impl NirvCenterConfigV3 {
         (ANA::from_decimal(reward_less_fee), ANA::from_decimal(fee))
     }
 
+    /// Utilization of the protocol's NIRV borrow capacity
+    ///
+    /// The capacity is the floor value of all staked ANA,
+    /// which is the same limit each borrower is held to.
+    pub fn nirv_utilization(&self, total_ana_staked: ANA, floor_price: PreciseNumber) -> Decimal {
+        let capacity = total_ana_staked
+            .to_decimal()
+            .checked_mul(floor_price.into())
+            .unwrap();
+
+        if capacity == Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+
+        self.total_borrowed_nirv
+            .to_decimal()
+            .checked_div(capacity)
+            .unwrap()
+            .min(Decimal::ONE)
+    }
+
+    /// Calculate the yearly NIRV borrow rate for a utilization.
+    ///
+    /// The rate climbs from the base rate by nirv_rate_slope until the
+    /// optimal utilization, then by the steeper nirv_jump_rate_slope until
+    /// full utilization. For example, with a 2% base, a 4% slope, an 80%
+    /// optimal utilization and a 60% jump slope, a utilization of 40% costs
+    /// 2% + 4% * (40% / 80%) = 4% and a utilization of 90% costs
+    /// 2% + 4% + 60% * (10% / 20%) = 36%.
+    pub fn calc_nirv_borrow_rate(&self, utilization: Decimal) -> Decimal {
+        let base_rate = self.nirv_base_rate.to_decimal();
+        let rate_slope = self.nirv_rate_slope.to_decimal();
+        let optimal = self.nirv_optimal_utilization.to_decimal();
+        let jump_rate_slope = self.nirv_jump_rate_slope.to_decimal();
+
+        let rate = if utilization <= optimal {
+            if optimal == Decimal::ZERO {
+                base_rate
+            } else {
+                utilization
+                    .checked_mul(rate_slope)
+                    .unwrap()
+                    .checked_div(optimal)
+                    .unwrap()
+                    .checked_add(base_rate)
+                    .unwrap()
+            }
+        } else {
+            let excess = utilization
+                .checked_sub(optimal)
+                .unwrap()
+                .checked_div(Decimal::ONE.checked_sub(optimal).unwrap())
+                .unwrap();
+
+            excess
+                .checked_mul(jump_rate_slope)
+                .unwrap()
+                .checked_add(rate_slope)
+                .unwrap()
+                .checked_add(base_rate)
+                .unwrap()
+        };
+
+        rate.round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero)
+    }
+
+    /// Accrue interest on all outstanding NIRV debt since the last accrual
+    pub fn accrue_nirv_interest(
+        &mut self,
+        now: u64,
+        total_ana_staked: ANA,
+        floor_price: PreciseNumber,
+    ) {
+        // configs created before interest existed start from an index of one
+        if self.nirv_borrow_index.val == 0 {
+            self.nirv_borrow_index = PreciseNumber::new(1);
+        }
+
+        // nothing has been accrued yet, so start the clock now
+        if self.time_of_last_nirv_accrual == 0 {
+            self.time_of_last_nirv_accrual = now;
+            return;
+        }
+
+        if now <= self.time_of_last_nirv_accrual {
+            return;
+        }
+
+        let elapsed = now - self.time_of_last_nirv_accrual;
+        self.time_of_last_nirv_accrual = now;
+
+        let utilization = self.nirv_utilization(total_ana_staked, floor_price);
+        let rate = self.calc_nirv_borrow_rate(utilization);
+
+        if rate == Decimal::ZERO {
+            return;
+        }
+
+        let growth = rate
+            .checked_mul(Decimal::from_u64(elapsed).unwrap())
+            .unwrap()
+            .checked_div(Decimal::new(SECONDS_PER_YEAR, 0))
+            .unwrap()
+            .checked_add(Decimal::ONE)
+            .unwrap();
+
+        let new_index = self
+            .nirv_borrow_index
+            .to_decimal()
+            .checked_mul(growth)
+            .unwrap()
+            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero);
+        self.nirv_borrow_index = PreciseNumber::from_decimal(new_index);
+
+        let new_total = self
+            .total_borrowed_nirv
+            .to_decimal()
+            .checked_mul(growth)
+            .unwrap()
+            .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::ToZero);
+        self.total_borrowed_nirv = NIRV::from_decimal(new_total);
+    }
+
+    pub fn add_borrowed_nirv(&mut self, amount: NIRV) {
+        self.total_borrowed_nirv += amount;
+    }
+
+    pub fn sub_borrowed_nirv(&mut self, amount: NIRV) {
+        // rounding in the accrual can leave the total a little under the sum of all debts
+        self.total_borrowed_nirv =
+            NIRV::from_u64(self.total_borrowed_nirv.val.saturating_sub(amount.val));
+    }
+
+    pub fn add_lock_boost(&mut self, boost: ANA) {
+        self.total_lock_boost += boost;
+    }
+
+    pub fn sub_lock_boost(&mut self, boost: ANA) {
+        self.total_lock_boost = ANA::from_u64(self.total_lock_boost.val.saturating_sub(boost.val));
+    }
+
     pub fn drop_prana_reward(
         &mut self,
         ana_supply: ANA,
         total_ana_staked: ANA,
         reward_interval_seconds: i64,
-    ) {
+    ) -> Result<()> {
         // TODO - use time since last drop to calculate amount that should be dropped
         // get propotion of reward interval for daily rate
         let seconds_in_day = 24 * 60 * 60;
@@ -328,31 +604,327 @@ impl NirvCenterConfigV3 {
             .checked_div(Decimal::new(seconds_in_day, 0))
             .unwrap();
 
-        let reward = self
+        let emission = self
             .prana_reward_rate
             .to_decimal()
             .checked_mul(reward_interval_ratio)
+            .unwrap();
+
+        self.drop_prana_emission(ana_supply, total_ana_staked, emission)
+    }
+
+    /// Drop prANA for an emission, the daily rate times the days it ran for
+    pub fn drop_prana_emission(
+        &mut self,
+        ana_supply: ANA,
+        total_ana_staked: ANA,
+        emission: Decimal,
+    ) -> Result<()> {
+        let dropped_amount = Self::prana_for_emission(ana_supply, emission);
+        msg!("Dropped amount: {}", dropped_amount.val);
+
+        self.drop_prana_amount(dropped_amount, total_ana_staked)
+    }
+
+    /// Drop prANA for an emission, less the bounty for the keeper that called it
+    ///
+    /// Returns the bounty, which is nothing when there is no one to drop to.
+    pub fn drop_prana_emission_with_keeper_bounty(
+        &mut self,
+        ana_supply: ANA,
+        total_ana_staked: ANA,
+        emission: Decimal,
+    ) -> Result<ANA> {
+        let dropped_amount = Self::prana_for_emission(ana_supply, emission);
+
+        if self.total_reward_weight(total_ana_staked) == ANA::ZERO {
+            return Ok(ANA::ZERO);
+        }
+
+        let bounty = self.keeper_bounty(dropped_amount);
+        let dropped_amount = dropped_amount - bounty;
+        msg!("Dropped amount: {}", dropped_amount.val);
+        msg!("Keeper bounty: {}", bounty.val);
+
+        self.drop_prana_amount(dropped_amount, total_ana_staked)?;
+
+        Ok(bounty)
+    }
+
+    /// Bounty for a keeper dropping an amount of prANA
+    ///
+    /// The share plus the flat amount, up to the cap, and never more than the drop.
+    pub fn keeper_bounty(&self, dropped_amount: ANA) -> ANA {
+        let share = dropped_amount
+            .to_decimal()
+            .checked_mul(self.keeper_bounty_share.to_decimal())
             .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+        let bounty = ANA::from_decimal(share)
+            .val
+            .saturating_add(self.keeper_bounty_flat.val);
+
+        ANA::from_u64(
+            bounty
+                .min(self.keeper_bounty_cap.val)
+                .min(dropped_amount.val),
+        )
+    }
+
+    fn prana_for_emission(ana_supply: ANA, emission: Decimal) -> ANA {
+        let reward = emission
             .checked_mul(ana_supply.into())
             .unwrap()
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
 
-        let dropped_amount = ANA::from_decimal(reward);
-        msg!("Dropped amount: {}", dropped_amount.val);
+        ANA::from_decimal(reward)
+    }
 
+    /// Staked ANA earning rewards, plus the lock boost
+    pub fn total_reward_weight(&self, total_ana_staked: ANA) -> ANA {
         if total_ana_staked.val == 0 {
-            return;
+            return ANA::ZERO;
+        }
+
+        ANA::from_u64(
+            (total_ana_staked + self.total_lock_boost)
+                .val
+                .saturating_sub(self.total_pending_unstake.val),
+        )
+    }
+
+    fn drop_prana_amount(&mut self, dropped_amount: ANA, total_ana_staked: ANA) -> Result<()> {
+        let total_reward_weight = self.total_reward_weight(total_ana_staked);
+
+        if total_reward_weight.val == 0 {
+            return Ok(());
         }
 
         let reward_index_add = dropped_amount
             .to_decimal()
-            .checked_div(total_ana_staked.into())
+            .checked_div(total_reward_weight.into())
             .unwrap()
-            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::ToZero);
+            .round_dp_with_strategy(IndexNumber::SCALE, RoundingStrategy::ToZero);
 
         msg!("Reward index delta: {}", reward_index_add);
-        let reward_index_add = PreciseNumber::from_decimal(reward_index_add);
+        let reward_index_add = IndexNumber::checked_from_decimal(reward_index_add)?;
         self.reward_index += reward_index_add;
         //TODO: subtract NIRV debt fee and add it to nirv fee index
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rate_model_config() -> NirvCenterConfigV3 {
+        NirvCenterConfigV3 {
+            // 2%
+            nirv_base_rate: CoarseNumber { val: 20_000 },
+            // 4%
+            nirv_rate_slope: CoarseNumber { val: 40_000 },
+            // 80%
+            nirv_optimal_utilization: CoarseNumber { val: 800_000 },
+            // 60%
+            nirv_jump_rate_slope: CoarseNumber { val: 600_000 },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nirv_borrow_rate() {
+        let c = rate_model_config();
+
+        assert_eq!(c.calc_nirv_borrow_rate(Decimal::ZERO), Decimal::new(2, 2));
+        assert_eq!(
+            c.calc_nirv_borrow_rate(Decimal::new(4, 1)),
+            Decimal::new(4, 2)
+        );
+        assert_eq!(
+            c.calc_nirv_borrow_rate(Decimal::new(8, 1)),
+            Decimal::new(6, 2)
+        );
+        assert_eq!(
+            c.calc_nirv_borrow_rate(Decimal::new(9, 1)),
+            Decimal::new(36, 2)
+        );
+        assert_eq!(c.calc_nirv_borrow_rate(Decimal::ONE), Decimal::new(66, 2));
+
+        // no rate model means no interest
+        let c = NirvCenterConfigV3 {
+            ..Default::default()
+        };
+        assert_eq!(c.calc_nirv_borrow_rate(Decimal::new(5, 1)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn nirv_utilization() {
+        let c = NirvCenterConfigV3 {
+            total_borrowed_nirv: NIRV::new(50),
+            ..Default::default()
+        };
+        let floor = PreciseNumber::new(1);
+
+        assert_eq!(c.nirv_utilization(ANA::ZERO, floor), Decimal::ZERO);
+        assert_eq!(c.nirv_utilization(ANA::new(100), floor), Decimal::new(5, 1));
+        assert_eq!(c.nirv_utilization(ANA::new(25), floor), Decimal::ONE);
+    }
+
+    #[test]
+    fn accrue_nirv_interest() {
+        let mut c = NirvCenterConfigV3 {
+            total_borrowed_nirv: NIRV::new(50),
+            ..rate_model_config()
+        };
+        let floor = PreciseNumber::new(1);
+
+        // the first accrual only starts the clock
+        c.accrue_nirv_interest(1_000, ANA::new(100), floor);
+        assert_eq!(c.nirv_borrow_index.val, PreciseNumber::new(1).val);
+        assert_eq!(c.time_of_last_nirv_accrual, 1_000);
+
+        // 50% utilization is a 4.5% yearly rate
+        c.accrue_nirv_interest(1_000 + SECONDS_PER_YEAR as u64, ANA::new(100), floor);
+        assert_eq!(c.nirv_borrow_index.val, 1_045_000_000_000);
+        assert_eq!(c.total_borrowed_nirv, NIRV { val: 52_250_000 });
+
+        // no time has passed
+        c.accrue_nirv_interest(1_000 + SECONDS_PER_YEAR as u64, ANA::new(100), floor);
+        assert_eq!(c.nirv_borrow_index.val, 1_045_000_000_000);
+    }
+
+    #[test]
+    fn collect_psm_fee() {
+        let mut c = NirvCenterConfigV3 {
+            // 0.1%
+            psm_mint_fee: CoarseNumber { val: 1_000 },
+            // 0.5%
+            psm_redeem_fee: CoarseNumber { val: 5_000 },
+            ..Default::default()
+        };
+
+        let (less_fee, fee) = c
+            .collect_psm_fee(true, NIRV::new(1_000), ALMS::new(10))
+            .unwrap();
+        assert_eq!(less_fee, NIRV::new(999));
+        assert_eq!(fee, NIRV::new(1));
+        assert_eq!(c.nirv_fee_index.val, 100_000_000_000_000_000);
+
+        let (less_fee, fee) = c
+            .collect_psm_fee(false, NIRV::new(1_000), ALMS::new(10))
+            .unwrap();
+        assert_eq!(less_fee, NIRV::new(995));
+        assert_eq!(fee, NIRV::new(5));
+        assert_eq!(c.nirv_fee_index.val, 600_000_000_000_000_000);
+    }
+
+    #[test]
+    fn collect_ana_unstake_and_sell_fee() {
+        let mut c = NirvCenterConfigV3 {
+            // 1%
+            unstake_fee: CoarseNumber { val: 10_000 },
+            // 2%
+            sell_fee: CoarseNumber { val: 20_000 },
+            ..Default::default()
+        };
+
+        let (less_fee, fee) = c
+            .collect_ana_unstake_and_sell_fee(ANA::new(100), ALMS::new(10))
+            .unwrap();
+        assert_eq!(less_fee, ANA::new(97));
+        assert_eq!(fee, ANA::new(3));
+        assert_eq!(c.ana_fee_index.val, 300_000_000_000_000_000);
+    }
+
+    #[test]
+    fn drop_prana_reward_with_lock_boost() {
+        let mut c = NirvCenterConfigV3 {
+            // 1% a day
+            prana_reward_rate: PreciseNumber {
+                val: 10_000_000_000,
+            },
+            total_lock_boost: ANA::new(100),
+            ..Default::default()
+        };
+
+        // 10 ANA dropped over 100 staked and 100 of lock boost
+        c.drop_prana_reward(ANA::new(1_000), ANA::new(100), 24 * 60 * 60)
+            .unwrap();
+        assert_eq!(c.reward_index.val, 50_000_000_000_000_000);
+    }
+
+    #[test]
+    fn drop_prana_reward_with_pending_unstake() {
+        let mut c = NirvCenterConfigV3 {
+            // 1% a day
+            prana_reward_rate: PreciseNumber {
+                val: 10_000_000_000,
+            },
+            total_pending_unstake: ANA::new(50),
+            ..Default::default()
+        };
+
+        // 10 ANA dropped over the 50 of 100 that is still staked
+        c.drop_prana_reward(ANA::new(1_000), ANA::new(100), 24 * 60 * 60)
+            .unwrap();
+        assert_eq!(c.reward_index.val, 200_000_000_000_000_000);
+    }
+
+    #[test]
+    fn keeper_bounty() {
+        let mut c = NirvCenterConfigV3 {
+            // 1%
+            keeper_bounty_share: CoarseNumber { val: 10_000 },
+            keeper_bounty_flat: ANA::new(1),
+            keeper_bounty_cap: ANA::new(5),
+            ..Default::default()
+        };
+
+        assert_eq!(c.keeper_bounty(ANA::new(100)), ANA::new(2));
+        assert_eq!(c.keeper_bounty(ANA::new(1_000)), ANA::new(5));
+        assert_eq!(
+            c.keeper_bounty(ANA::from_u64(500_000)),
+            ANA::from_u64(500_000)
+        );
+
+        // 1% a day on 1,000 ANA is 10 dropped, 1.1 of it to the keeper
+        let emission = Decimal::new(1, 2);
+        let bounty = c
+            .drop_prana_emission_with_keeper_bounty(ANA::new(1_000), ANA::new(100), emission)
+            .unwrap();
+        assert_eq!(bounty, ANA::from_u64(1_100_000));
+        assert_eq!(c.reward_index.val, 89_000_000_000_000_000);
+
+        // nothing to drop to, no bounty
+        let bounty = c
+            .drop_prana_emission_with_keeper_bounty(ANA::new(1_000), ANA::ZERO, emission)
+            .unwrap();
+        assert_eq!(bounty, ANA::ZERO);
+    }
+
+    #[test]
+    fn drop_dust_over_large_stake() {
+        let mut c = NirvCenterConfigV3::default();
+
+        // 0.000001 ANA over 1B staked would round to nothing at 12 decimals
+        c.drop_prana_amount(ANA::from_u64(1), ANA::new(1_000_000_000))
+            .unwrap();
+        assert_eq!(c.reward_index.val, 1_000);
+    }
+
+    #[test]
+    fn drop_onto_large_index() {
+        let mut c = NirvCenterConfigV3 {
+            // Past what a u64 holds at 12 decimals
+            reward_index: IndexNumber::new(20_000_000),
+            ..Default::default()
+        };
+
+        // 1M ANA over 1 staked
+        c.drop_prana_amount(ANA::new(1_000_000), ANA::new(1))
+            .unwrap();
+        assert_eq!(c.reward_index, IndexNumber::new(21_000_000));
     }
 }
@@ -0,0 +1,287 @@
+use crate::errors::ErrorCode;
+use crate::numbers::{IndexNumber, ANA};
+use crate::state::UserRewardV2;
+use anchor_lang::prelude::*;
+use rust_decimal::prelude::*;
+use std::cmp;
+use std::convert::TryInto;
+
+/// Campaign paying an SPL token to ANA stakers
+///
+/// Tokens are emitted at a fixed rate between the start and end time and shared
+/// by the reward weight of the stakers who joined. The index tracks tokens earned
+/// per unit of weight.
+#[account]
+#[derive(Default, Debug)]
+pub struct RewardStream {
+    /// Link to NirvCenter
+    pub nirv_center: Pubkey,
+
+    /// Mint of the token paid out
+    pub reward_mint: Pubkey,
+
+    /// Decimals of the token paid out
+    pub decimals: u8,
+
+    /// Token account holding the funded rewards
+    pub vault: Pubkey,
+
+    /// Tokens emitted per second, in the smallest unit of the token
+    pub emission_per_second: u64,
+
+    /// When emission starts
+    pub start_time: u64,
+
+    /// When emission ends
+    pub end_time: u64,
+
+    /// Tokens earned per unit of reward weight since the start
+    pub index: IndexNumber,
+
+    /// When the index was last updated
+    pub last_update_time: u64,
+
+    /// Tokens funded into the vault
+    pub total_funded: u64,
+
+    /// Tokens claimed out of the vault
+    pub total_claimed: u64,
+
+    pub bump: u8,
+
+    /// Reward weight of the stakers who joined, as recorded in their checkpoints
+    pub participating_weight: ANA,
+
+    /// Tokens emitted while someone was participating, owed to the checkpoints
+    pub total_emitted: u64,
+}
+
+impl RewardStream {
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.end_time
+    }
+
+    /// Emit the tokens since the last update over the participating weight
+    ///
+    /// Nothing is owed for the time no one participated, it can be reclaimed.
+    pub fn update_index(&mut self, now: u64) -> Result<()> {
+        let from = cmp::min(
+            cmp::max(self.last_update_time, self.start_time),
+            self.end_time,
+        );
+        let to = cmp::min(cmp::max(now, self.start_time), self.end_time);
+        self.last_update_time = cmp::max(self.last_update_time, now);
+
+        if to <= from || self.participating_weight == ANA::ZERO {
+            return Ok(());
+        }
+
+        let emitted = (to - from).checked_mul(self.emission_per_second).unwrap();
+        let index_add = Decimal::new(emitted.try_into().unwrap(), self.decimals.into())
+            .checked_div(self.participating_weight.into())
+            .unwrap()
+            .round_dp_with_strategy(IndexNumber::SCALE, RoundingStrategy::ToZero);
+
+        self.index += IndexNumber::checked_from_decimal(index_add)?;
+        self.total_emitted = self.total_emitted.checked_add(emitted).unwrap();
+
+        Ok(())
+    }
+
+    /// Record tokens paid out of the vault
+    pub fn claim(&mut self, amount: u64) {
+        self.total_claimed = self.total_claimed.checked_add(amount).unwrap();
+    }
+
+    /// Settle a checkpoint and move its weight in the stream to the new one
+    pub fn reweight(&mut self, checkpoint: &mut StreamCheckpoint, weight: ANA) -> Result<()> {
+        checkpoint.settle(self)?;
+
+        self.participating_weight -= checkpoint.weight;
+        self.participating_weight += weight;
+        checkpoint.weight = weight;
+
+        Ok(())
+    }
+
+    /// What the vault holds beyond what is still owed to the checkpoints
+    pub fn reclaimable(&self, vault_balance: u64) -> u64 {
+        let owed = self.total_emitted.saturating_sub(self.total_claimed);
+
+        vault_balance.saturating_sub(owed)
+    }
+}
+
+/// A staker's progress in a reward stream
+///
+/// Created the first time the staker joins the stream and earns from then on.
+#[account]
+#[derive(Default, Debug)]
+pub struct StreamCheckpoint {
+    /// Link to RewardStream
+    pub reward_stream: Pubkey,
+
+    /// Owner of the UserRewardV2 earning from the stream
+    pub owner: Pubkey,
+
+    /// The stream index when the checkpoint was last settled
+    pub index: IndexNumber,
+
+    /// Reward weight of the owner when the checkpoint was last settled
+    pub weight: ANA,
+
+    /// Tokens earned and not yet claimed, in the smallest unit of the token
+    pub accrued: u64,
+
+    pub bump: u8,
+}
+
+impl StreamCheckpoint {
+    /// Credit what was earned on the recorded weight since the last settlement
+    pub fn settle(&mut self, stream: &RewardStream) -> Result<()> {
+        let index_delta = (stream.index - self.index).checked_to_decimal()?;
+
+        let mut earned = index_delta
+            .checked_mul(self.weight.into())
+            .unwrap()
+            .round_dp_with_strategy(stream.decimals.into(), RoundingStrategy::ToZero);
+        earned.rescale(stream.decimals.into());
+
+        self.accrued = self
+            .accrued
+            .checked_add(earned.mantissa().to_u64().unwrap())
+            .unwrap();
+        self.index = stream.index;
+
+        Ok(())
+    }
+
+    /// Take what can be paid out of the vault
+    pub fn take_accrued(&mut self, vault_balance: u64) -> u64 {
+        let amount = cmp::min(self.accrued, vault_balance);
+        self.accrued -= amount;
+
+        amount
+    }
+}
+
+/// Settle the reward streams passed as pairs of RewardStream and StreamCheckpoint
+///
+/// Called after every change to the user's reward weight. Every stream the user
+/// joined has to be passed, so that the weight recorded in each checkpoint
+/// follows the change to the stake.
+pub fn settle_reward_streams<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    program_id: &Pubkey,
+    user_reward: &UserRewardV2,
+    now: u64,
+) -> Result<()> {
+    if remaining_accounts.len() != 2 * user_reward.stream_count as usize {
+        return Err(ErrorCode::MissingRewardStreams.into());
+    }
+
+    let weight = user_reward.effective_staked_amount();
+    let mut settled: Vec<Pubkey> = Vec::with_capacity(user_reward.stream_count.into());
+
+    for pair in remaining_accounts.chunks(2) {
+        let mut stream: Account<'info, RewardStream> = Account::try_from(&pair[0])?;
+        let mut checkpoint: Account<'info, StreamCheckpoint> = Account::try_from(&pair[1])?;
+
+        if checkpoint.reward_stream != stream.key()
+            || checkpoint.owner != user_reward.owner
+            || settled.contains(&checkpoint.key())
+        {
+            return Err(ErrorCode::InvalidStreamCheckpoint.into());
+        }
+        settled.push(checkpoint.key());
+
+        stream.update_index(now)?;
+        stream.reweight(&mut checkpoint, weight)?;
+
+        stream.exit(program_id)?;
+        checkpoint.exit(program_id)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn stream() -> RewardStream {
+        RewardStream {
+            decimals: 6,
+            // 1 token a second
+            emission_per_second: 1_000_000,
+            start_time: 100,
+            end_time: 200,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn update_index_within_window() {
+        let mut s = RewardStream {
+            participating_weight: ANA::new(10),
+            ..stream()
+        };
+
+        // before the start
+        s.update_index(50).unwrap();
+        assert_eq!(s.index.val, 0);
+
+        // 10 tokens over 10 weight
+        s.update_index(110).unwrap();
+        assert_eq!(s.index.val, 1_000_000_000_000_000_000);
+        assert_eq!(s.total_emitted, 10_000_000);
+
+        // no one participating, nothing emitted, but time moves on
+        s.participating_weight = ANA::ZERO;
+        s.update_index(120).unwrap();
+        assert_eq!(s.index.val, 1_000_000_000_000_000_000);
+        assert_eq!(s.total_emitted, 10_000_000);
+
+        // only up to the end
+        s.participating_weight = ANA::new(80);
+        s.update_index(500).unwrap();
+        assert_eq!(s.index.val, 2_000_000_000_000_000_000);
+        assert_eq!(s.total_emitted, 90_000_000);
+        assert_eq!(s.last_update_time, 500);
+        assert!(!s.is_active(500));
+
+        // the 10 seconds no one participated can be reclaimed
+        s.claim(30_000_000);
+        assert_eq!(s.reclaimable(100_000_000), 40_000_000);
+    }
+
+    #[test]
+    fn settle_and_take() {
+        let mut s = stream();
+        let mut c = StreamCheckpoint::default();
+        let mut other = StreamCheckpoint::default();
+        s.reweight(&mut c, ANA::new(5)).unwrap();
+        s.reweight(&mut other, ANA::new(5)).unwrap();
+        assert_eq!(s.participating_weight, ANA::new(10));
+
+        s.update_index(110).unwrap();
+        s.reweight(&mut c, ANA::new(5)).unwrap();
+        assert_eq!(c.accrued, 5_000_000);
+        assert_eq!(c.index.val, s.index.val);
+
+        // weight removed earns nothing, even once it is restored
+        s.reweight(&mut c, ANA::ZERO).unwrap();
+        s.update_index(120).unwrap();
+        s.reweight(&mut c, ANA::new(5)).unwrap();
+        assert_eq!(c.accrued, 5_000_000);
+
+        // while the other checkpoint earned all of it
+        s.reweight(&mut other, ANA::new(5)).unwrap();
+        assert_eq!(other.accrued, 15_000_000);
+
+        assert_eq!(c.take_accrued(4_000_000), 4_000_000);
+        assert_eq!(c.accrued, 1_000_000);
+        assert_eq!(c.take_accrued(10_000_000), 1_000_000);
+        assert_eq!(c.accrued, 0);
+    }
+}
@@ -0,0 +1,165 @@
+use crate::numbers::{Decimalable, ANA};
+use crate::state::{NirvCenterConfigV3, PriceFieldV2, UserRewardV2};
+use anchor_lang::prelude::*;
+use rust_decimal::prelude::*;
+
+/// Liquid staking vault
+///
+/// The vault stakes ANA through its own UserRewardV2 position, owned by the vault,
+/// and compounds the prANA it earns back into the stake.
+/// sANA is a claim on a share of that stake.
+/// The vault never borrows NIRV, since it can't sign for its own position.
+#[account]
+#[derive(Default, Debug)]
+pub struct SanaVault {
+    /// Link to NirvCenter
+    pub nirv_center: Pubkey,
+
+    /// Mint for the sANA receipt token
+    pub mint_sana: Pubkey,
+
+    pub bump: u8,
+}
+
+/// sANA that exists only in the exchange rate, so the first depositor can't inflate it
+pub const VIRTUAL_SANA: u64 = 1_000_000;
+
+/// ANA that exists only in the exchange rate, paired with VIRTUAL_SANA
+pub const VIRTUAL_ANA: u64 = 1_000_000;
+
+impl SanaVault {
+    /// ANA backing the sANA when depositing: the stake plus the prANA not compounded yet
+    ///
+    /// The prANA is valued as ANA, more than compounding realizes, so a deposit made
+    /// just before a compound never takes the yield holders have already earned.
+    pub fn deposit_assets(
+        vault_reward: &UserRewardV2,
+        config: &NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<ANA> {
+        let (pending, _) = vault_reward.calc_rewards_and_fees(config, price_field)?;
+
+        Ok(vault_reward.staked_amount + vault_reward.staged_pre_ana_rewards + pending)
+    }
+
+    /// sANA minted for ANA deposited, at the current exchange rate
+    ///
+    /// Since tokens out, round down
+    pub fn sana_for_deposit(ana_amount: ANA, vault_assets: ANA, sana_supply: u64) -> u64 {
+        let sana = ana_amount
+            .to_decimal()
+            .checked_mul(ANA::from_u64(sana_supply + VIRTUAL_SANA).to_decimal())
+            .unwrap()
+            .checked_div(ANA::from_u64(vault_assets.val + VIRTUAL_ANA).to_decimal())
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+        ANA::from_decimal(sana).into()
+    }
+
+    /// ANA unstaked for sANA withdrawn, at the current exchange rate
+    ///
+    /// Since tokens out, round down
+    pub fn ana_for_withdrawal(sana_amount: u64, vault_staked: ANA, sana_supply: u64) -> ANA {
+        let ana = ANA::from_u64(vault_staked.val + VIRTUAL_ANA)
+            .to_decimal()
+            .checked_mul(ANA::from_u64(sana_amount).to_decimal())
+            .unwrap()
+            .checked_div(ANA::from_u64(sana_supply + VIRTUAL_SANA).to_decimal())
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+        let ana = ANA::from_decimal(ana);
+        if ana.val > vault_staked.val {
+            vault_staked
+        } else {
+            ana
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::numbers::IndexNumber;
+
+    #[test]
+    fn first_deposit_is_one_to_one() {
+        assert_eq!(
+            SanaVault::sana_for_deposit(ANA::new(10), ANA::ZERO, 0),
+            10_000_000
+        );
+    }
+
+    #[test]
+    fn exchange_rate_rises_with_compounding() {
+        // 99 sANA backed by 124 ANA after compounding, 100 and 125 with the virtual amounts
+        let staked = ANA::new(124);
+        let supply = 99_000_000;
+
+        assert_eq!(
+            SanaVault::sana_for_deposit(ANA::new(10), staked, supply),
+            8_000_000
+        );
+        assert_eq!(
+            SanaVault::ana_for_withdrawal(8_000_000, staked, supply),
+            ANA::new(10)
+        );
+
+        // the virtual ANA is never paid out
+        assert_eq!(
+            SanaVault::ana_for_withdrawal(supply, staked, supply),
+            ANA::from_u64(123_750_000)
+        );
+    }
+
+    #[test]
+    fn rounds_in_favor_of_the_vault() {
+        // 2 sANA backed by 3 ANA with the virtual amounts
+        let staked = ANA::new(2);
+        let supply = 1_000_000;
+
+        // 1 ANA buys 0.666666 sANA
+        assert_eq!(
+            SanaVault::sana_for_deposit(ANA::new(1), staked, supply),
+            666_666
+        );
+        // which is worth 0.999999 ANA
+        assert_eq!(
+            SanaVault::ana_for_withdrawal(666_666, staked, supply),
+            ANA::from_u64(999_999)
+        );
+    }
+
+    #[test]
+    fn donation_does_not_inflate_shares() {
+        // the first depositor puts in a millionth of an ANA then donates 1,000 ANA
+        let supply = SanaVault::sana_for_deposit(ANA::from_u64(1), ANA::ZERO, 0);
+        assert_eq!(supply, 1);
+        let staked = ANA::from_u64(1) + ANA::new(1_000);
+
+        // the next depositor still gets sANA, and loses only rounding dust
+        let sana = SanaVault::sana_for_deposit(ANA::new(10), staked, supply);
+        assert!(sana > 0);
+        let ana = SanaVault::ana_for_withdrawal(sana, staked + ANA::new(10), supply + sana);
+        assert_eq!(ana, ANA::from_u64(9_999_980));
+    }
+
+    #[test]
+    fn deposits_count_pending_rewards() {
+        let config = NirvCenterConfigV3 {
+            reward_index: IndexNumber::new(1),
+            ..Default::default()
+        };
+        let vault_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            staged_pre_ana_rewards: ANA::new(5),
+            ..Default::default()
+        };
+
+        // 100 staked, 5 staged and 100 earned since the index
+        let assets =
+            SanaVault::deposit_assets(&vault_reward, &config, &PriceFieldV2::default()).unwrap();
+        assert_eq!(assets, ANA::new(205));
+    }
+}
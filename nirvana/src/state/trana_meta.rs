@@ -1,5 +1,7 @@
-use crate::numbers::{ArbitraryNumber, PreciseNumber, ANA};
+use crate::errors::ErrorCode;
+use crate::numbers::{ArbitraryNumber, CoarseNumber, Decimalable, PreciseNumber, ANA};
 use anchor_lang::prelude::*;
+use rust_decimal::prelude::*;
 
 #[account]
 #[derive(Default)]
@@ -10,7 +12,7 @@ pub struct TranaMeta {
     /// Whether this bond is active
     pub enabled: bool,
 
-    /// controls the slope of the price function
+    /// controls the slope of the price function, applied to the debt as a share of ANA supply
     pub sensitivity: PreciseNumber,
 
     /// Max discount ratio
@@ -27,10 +29,197 @@ pub struct TranaMeta {
 
     /// Maximum vesting time
     pub vesting_length_seconds: u64,
+
+    /// Most ANA that can be sold through this bond, zero for no limit
+    pub capacity: ANA,
+
+    /// How much ANA has been sold through this bond
+    pub ana_sold: ANA,
+
+    /// Most ANA a single purchase can buy, zero for no limit
+    pub max_per_purchase: ANA,
+
+    /// Most ANA a single user can buy, zero for no limit
+    pub max_per_user: ANA,
+
+    /// When sales open
+    pub open_time: i64,
+
+    /// When sales close, zero for never
+    pub close_time: i64,
+
+    /// ANA sold through this bond, decaying to nothing over the vesting length
+    pub debt: ANA,
+
+    /// When the debt last decayed
+    pub last_decay_time: i64,
+
+    /// ANA of debt decayed each second, so the debt is gone one vesting length after the last purchase
+    pub debt_decay_per_second: PreciseNumber,
+
+    /// How long after purchase before anything vests
+    pub cliff_seconds: u64,
+
+    /// Vested ANA is released in steps this long, zero for a linear release
+    pub release_step_seconds: u64,
+
+    /// Can bonds redeem their unvested ANA early, less the penalty?
+    pub early_exit_enabled: bool,
+
+    /// Share of the unvested ANA kept on an early exit
+    pub early_exit_penalty: CoarseNumber,
 }
 
 impl TranaMeta {
     pub fn sub_ana_outstanding(&mut self, a: ANA) {
         self.ana_outstanding -= a;
     }
+
+    /// Decay the debt linearly at the stored rate, for the time since it last decayed
+    pub fn decay_debt(&mut self, now: i64) {
+        let elapsed = now
+            .saturating_sub(self.last_decay_time)
+            .max(0)
+            .unsigned_abs();
+        self.last_decay_time = now;
+
+        let decay = self
+            .debt_decay_per_second
+            .to_decimal()
+            .checked_mul(elapsed.into())
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::AwayFromZero)
+            .min(self.debt.to_decimal());
+
+        self.debt -= ANA::from_decimal(decay);
+        if self.debt == ANA::ZERO {
+            self.debt_decay_per_second = PreciseNumber::ZERO;
+        }
+    }
+
+    /// Add newly sold ANA to the debt, which then decays over a full vesting length
+    ///
+    /// The debt should be decayed up to now first.
+    pub fn add_debt(&mut self, amount: ANA) {
+        self.debt += amount;
+
+        // Round up so the debt is gone by the end
+        let rate = self
+            .debt
+            .to_decimal()
+            .checked_div(self.vesting_length_seconds.into())
+            .unwrap_or_else(|| self.debt.to_decimal())
+            .round_dp_with_strategy(PreciseNumber::SCALE, RoundingStrategy::AwayFromZero);
+
+        self.debt_decay_per_second = PreciseNumber::from_decimal(rate);
+    }
+
+    /// Check a purchase fits the window and the limits, then record it
+    ///
+    /// `user_bought` is what the user bought from this bond before.
+    pub fn sell_ana(&mut self, ana_bought: ANA, user_bought: ANA, now: i64) -> Result<()> {
+        if now < self.open_time {
+            return Err(ErrorCode::TranaSaleNotOpen.into());
+        }
+
+        if self.close_time != 0 && now >= self.close_time {
+            return Err(ErrorCode::TranaSaleClosed.into());
+        }
+
+        if self.max_per_purchase != ANA::ZERO && ana_bought.val > self.max_per_purchase.val {
+            return Err(ErrorCode::TranaPurchaseTooLarge.into());
+        }
+
+        let user_total = user_bought.val.checked_add(ana_bought.val).unwrap();
+        if self.max_per_user != ANA::ZERO && user_total > self.max_per_user.val {
+            return Err(ErrorCode::TranaUserLimitReached.into());
+        }
+
+        let ana_sold = self.ana_sold.val.checked_add(ana_bought.val).unwrap();
+        if self.capacity != ANA::ZERO && ana_sold > self.capacity.val {
+            return Err(ErrorCode::TranaCapacityReached.into());
+        }
+
+        self.ana_sold = ANA::from_u64(ana_sold);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn limited_meta() -> TranaMeta {
+        TranaMeta {
+            capacity: ANA::new(100),
+            max_per_purchase: ANA::new(40),
+            max_per_user: ANA::new(60),
+            open_time: 1_000,
+            close_time: 2_000,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sell_ana_within_limits() {
+        let mut t = limited_meta();
+
+        t.sell_ana(ANA::new(40), ANA::ZERO, 1_000).unwrap();
+        t.sell_ana(ANA::new(20), ANA::new(40), 1_999).unwrap();
+        t.sell_ana(ANA::new(40), ANA::ZERO, 1_500).unwrap();
+        assert_eq!(t.ana_sold, ANA::new(100));
+
+        // No limits
+        let mut t = TranaMeta::default();
+        t.sell_ana(ANA::new(1_000_000), ANA::new(1_000_000), 0)
+            .unwrap();
+        assert_eq!(t.ana_sold, ANA::new(1_000_000));
+    }
+
+    #[test]
+    fn sell_ana_outside_limits() {
+        let mut t = limited_meta();
+
+        assert!(t.sell_ana(ANA::new(1), ANA::ZERO, 999).is_err());
+        assert!(t.sell_ana(ANA::new(1), ANA::ZERO, 2_000).is_err());
+        assert!(t.sell_ana(ANA::new(41), ANA::ZERO, 1_500).is_err());
+        assert!(t.sell_ana(ANA::new(21), ANA::new(40), 1_500).is_err());
+        assert_eq!(t.ana_sold, ANA::ZERO);
+
+        t.ana_sold = ANA::new(90);
+        assert!(t.sell_ana(ANA::new(11), ANA::ZERO, 1_500).is_err());
+        assert_eq!(t.ana_sold, ANA::new(90));
+    }
+
+    #[test]
+    fn decay_debt() {
+        let mut t = TranaMeta {
+            vesting_length_seconds: 1_000,
+            last_decay_time: 5_000,
+            ..Default::default()
+        };
+        t.add_debt(ANA::new(100));
+
+        t.decay_debt(5_250);
+        assert_eq!(t.debt, ANA::new(75));
+        assert_eq!(t.last_decay_time, 5_250);
+
+        // Nothing has passed
+        t.decay_debt(5_250);
+        assert_eq!(t.debt, ANA::new(75));
+
+        // Each quarter of the vesting length decays a quarter of the debt
+        t.decay_debt(5_500);
+        assert_eq!(t.debt, ANA::new(50));
+
+        // A purchase spreads the debt over a full vesting length again
+        t.add_debt(ANA::new(50));
+        t.decay_debt(6_000);
+        assert_eq!(t.debt, ANA::new(50));
+
+        t.decay_debt(6_500);
+        assert_eq!(t.debt, ANA::ZERO);
+        assert_eq!(t.debt_decay_per_second.val, 0);
+    }
 }
@@ -1,8 +1,9 @@
 use crate::errors::ErrorCode;
-use crate::numbers::{Decimalable, PreciseNumber, ALMS, ANA, NIRV};
+use crate::numbers::{Decimalable, IndexNumber, PreciseNumber, ALMS, ANA, NIRV};
 use crate::state::{NirvCenterConfigV3, PriceFieldV2};
 use anchor_lang::prelude::*;
 use rust_decimal::prelude::*;
+use std::convert::TryInto;
 use std::ops::Mul;
 
 #[account]
@@ -32,33 +33,34 @@ pub struct UserReward {
 
 #[allow(dead_code)]
 impl UserReward {
-    pub fn calc_rewards_before_fees(&self, central_reward_index: PreciseNumber) -> ANA {
-        let index_delta = central_reward_index - self.index;
+    pub fn calc_rewards_before_fees(&self, central_reward_index: IndexNumber) -> Result<ANA> {
+        let index_delta =
+            (central_reward_index - IndexNumber::from_precise(self.index)).checked_to_decimal()?;
 
         // Get the share of the reward pot
         let share = self
             .staked_amount
             .to_decimal()
-            .checked_mul(index_delta.into())
+            .checked_mul(index_delta)
             .unwrap()
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
 
-        ANA::from_decimal(share)
+        Ok(ANA::from_decimal(share))
     }
 
     pub fn calc_rewards_and_fees(
         &self,
         config: &NirvCenterConfigV3,
         price_field: &PriceFieldV2,
-    ) -> (ANA, ANA) {
+    ) -> Result<(ANA, ANA)> {
         let borrow_utilization = self.get_nirv_borrow_utilization(price_field).unwrap();
 
-        let total_rewards = self.calc_rewards_before_fees(config.reward_index);
+        let total_rewards = self.calc_rewards_before_fees(config.reward_index)?;
 
         let (rewards_less_fees, fees) =
             config.calc_nirv_debt_fee(total_rewards, borrow_utilization);
 
-        (rewards_less_fees, fees)
+        Ok((rewards_less_fees, fees))
     }
 
     /// Claim prana rewards
@@ -67,31 +69,37 @@ impl UserReward {
         config: &mut NirvCenterConfigV3,
         price_field: &PriceFieldV2,
         total_alms_staked: ALMS,
-    ) -> (ANA, ANA) {
-        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field);
+    ) -> Result<(ANA, ANA)> {
+        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field)?;
 
         let total_rewards = new_rewards + self.staged_pre_ana_rewards;
         let total_fees = new_fees + self.staged_pre_ana_fees;
 
-        config.collect_prana_fee(total_fees, total_alms_staked);
+        config.collect_prana_fee(total_fees, total_alms_staked)?;
 
         self.reset_rewards(config.reward_index);
 
-        (total_rewards, total_fees)
+        Ok((total_rewards, total_fees))
     }
 
     /// Stage the un-claimed rewards from the central index
-    pub fn stage_rewards(&mut self, config: &NirvCenterConfigV3, price_field: &PriceFieldV2) {
+    pub fn stage_rewards(
+        &mut self,
+        config: &NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
         // get new rewards
         let central_reward_index = config.reward_index;
-        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field);
+        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field)?;
 
         // stage them
         self.staged_pre_ana_rewards = self.staged_pre_ana_rewards + new_rewards;
         self.staged_pre_ana_fees = self.staged_pre_ana_fees + new_fees;
 
         // update the index
-        self.index = central_reward_index;
+        self.index = Self::narrow_index(central_reward_index);
+
+        Ok(())
     }
 
     /// Add to staked amount
@@ -100,11 +108,13 @@ impl UserReward {
         amount: ANA,
         config: &NirvCenterConfigV3,
         price_field: &PriceFieldV2,
-    ) {
+    ) -> Result<()> {
         // stage the unclaimed rewards
-        self.stage_rewards(config, price_field);
+        self.stage_rewards(config, price_field)?;
 
         self.staked_amount += amount;
+
+        Ok(())
     }
 
     /// Unstake ANA
@@ -116,7 +126,7 @@ impl UserReward {
         total_alms_staked: ALMS,
     ) -> Result<(ANA, ANA)> {
         // stage the unclaimed rewards
-        self.stage_rewards(config, price_field);
+        self.stage_rewards(config, price_field)?;
 
         self.staked_amount -= amount;
 
@@ -127,7 +137,7 @@ impl UserReward {
         }
 
         // Calculate fee
-        let (amount_less_fee, fee) = config.collect_ana_unstake_fee(amount, total_alms_staked);
+        let (amount_less_fee, fee) = config.collect_ana_unstake_fee(amount, total_alms_staked)?;
 
         Ok((amount_less_fee, fee))
     }
@@ -189,8 +199,22 @@ impl UserReward {
         Ok(())
     }
 
-    pub fn reset_rewards(&mut self, central_reward_index: PreciseNumber) {
-        self.index = central_reward_index;
+    /// The central index rounded down to what this account can hold
+    ///
+    /// Past what a PreciseNumber holds it saturates, which only a
+    /// v1 account that is being migrated away from ever sees.
+    fn narrow_index(central_reward_index: IndexNumber) -> PreciseNumber {
+        let narrow = 10u128.pow(IndexNumber::SCALE - PreciseNumber::SCALE);
+
+        PreciseNumber {
+            val: (central_reward_index.val / narrow)
+                .try_into()
+                .unwrap_or(u64::MAX),
+        }
+    }
+
+    pub fn reset_rewards(&mut self, central_reward_index: IndexNumber) {
+        self.index = Self::narrow_index(central_reward_index);
         self.staged_pre_ana_rewards = ANA::ZERO;
         self.staged_pre_ana_fees = ANA::ZERO;
     }
@@ -203,32 +227,32 @@ mod test {
     #[test]
     fn calc_new_rewards_zero() {
         // 0.001
-        let index = PreciseNumber { val: 1_000_000_000 };
+        let index = IndexNumber::from_precise(PreciseNumber { val: 1_000_000_000 });
 
         let user_reward = UserReward {
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
 
         let user_reward = UserReward {
             index: PreciseNumber { val: 1_000_000 },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
     }
 
     #[test]
     fn calc_new_rewards() {
         // 0.001
-        let index = PreciseNumber { val: 2_000_000_000 };
+        let index = IndexNumber::from_precise(PreciseNumber { val: 2_000_000_000 });
         let user_reward = UserReward {
             index: PreciseNumber { val: 1_000_000_000 },
             staked_amount: ANA { val: 3_000_000 },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 3_000);
 
         let user_reward = UserReward {
@@ -236,7 +260,18 @@ mod test {
             staked_amount: ANA { val: 3_000_000 },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
     }
+
+    #[test]
+    fn reset_rewards_saturates() {
+        // Past u64::MAX at 12 decimals
+        let index = IndexNumber::new(20_000_000);
+        let mut user_reward = UserReward {
+            ..Default::default()
+        };
+        user_reward.reset_rewards(index);
+        assert_eq!(user_reward.index.val, u64::MAX);
+    }
 }
@@ -1,15 +1,33 @@
 use crate::errors::ErrorCode;
-use crate::numbers::{Decimalable, PreciseNumber, ALMS, ANA, NIRV};
-use crate::state::{NirvCenterConfigV3, PriceFieldV2};
+use crate::numbers::{CoarseNumber, Decimalable, IndexNumber, PreciseNumber, ALMS, ANA, NIRV};
+use crate::state::{NirvCenterConfigV3, PriceFieldV2, UserReward};
 use anchor_lang::prelude::*;
 use rust_decimal::prelude::*;
 use std::ops::Mul;
 
+const SECONDS_PER_MONTH: u64 = 30 * 24 * 60 * 60;
+
+/// Lock durations and the reward weight they give the locked ANA
+pub const LOCK_TIERS: [(u64, CoarseNumber); 4] = [
+    (SECONDS_PER_MONTH, CoarseNumber { val: 1_100_000 }),
+    (3 * SECONDS_PER_MONTH, CoarseNumber { val: 1_250_000 }),
+    (6 * SECONDS_PER_MONTH, CoarseNumber { val: 1_500_000 }),
+    (12 * SECONDS_PER_MONTH, CoarseNumber { val: 2_000_000 }),
+];
+
+/// Reward weight for a lock duration, if it is one of the tiers
+pub fn lock_multiplier(duration_seconds: u64) -> Option<CoarseNumber> {
+    LOCK_TIERS
+        .iter()
+        .find(|(duration, _)| *duration == duration_seconds)
+        .map(|(_, multiplier)| *multiplier)
+}
+
 #[account]
 #[derive(Default, Debug)]
 pub struct UserRewardV2 {
     /// The user's reward index
-    pub index: PreciseNumber,
+    pub index: IndexNumber,
 
     /// User account that "owns" this
     pub owner: Pubkey,
@@ -17,7 +35,7 @@ pub struct UserRewardV2 {
     /// How much ANA is staked
     pub staked_amount: ANA,
 
-    /// How much NIRV is borrowed
+    /// How much NIRV is borrowed, including interest accrued up to the borrow index
     pub borrowed_nirv: NIRV,
 
     /// How much pre-ana is staged to claim?
@@ -27,36 +45,69 @@ pub struct UserRewardV2 {
     pub staged_pre_ana_fees: ANA,
 
     pub bump: u8,
+
+    /// The central NIRV borrow index when interest was last accrued
+    pub nirv_borrow_index: PreciseNumber,
+
+    /// Should prANA rewards be used to repay the borrowed NIRV?
+    pub self_repaying: bool,
+
+    /// Keeper allowed to compound rewards for the owner, if any
+    pub compound_keeper: Pubkey,
+
+    /// How much of the staked ANA is locked
+    pub locked_amount: ANA,
+
+    /// When the locked ANA can be unlocked
+    pub lock_end_time: u64,
+
+    /// Reward weight of the locked ANA
+    pub lock_multiplier: CoarseNumber,
+
+    /// ANA waiting out the unstake cooldown, no longer staked
+    pub pending_unstake_amount: ANA,
+
+    /// When the pending ANA can be withdrawn
+    pub unstake_available_time: u64,
+
+    /// Wallet the owner approved to claim, compound and restake for them, if any
+    pub delegate: Pubkey,
+
+    /// How many reward streams this account has joined
+    pub stream_count: u8,
 }
 
 impl UserRewardV2 {
-    pub fn calc_rewards_before_fees(&self, central_reward_index: PreciseNumber) -> ANA {
-        let index_delta = central_reward_index - self.index;
+    pub fn calc_rewards_before_fees(&self, central_reward_index: IndexNumber) -> Result<ANA> {
+        let index_delta = (central_reward_index - self.index).checked_to_decimal()?;
 
         // Get the share of the reward pot
         let share = self
-            .staked_amount
+            .effective_staked_amount()
             .to_decimal()
-            .checked_mul(index_delta.into())
+            .checked_mul(index_delta)
             .unwrap()
             .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
 
-        ANA::from_decimal(share)
+        Ok(ANA::from_decimal(share))
     }
 
     pub fn calc_rewards_and_fees(
         &self,
         config: &NirvCenterConfigV3,
         price_field: &PriceFieldV2,
-    ) -> (ANA, ANA) {
-        let borrow_utilization = self.get_nirv_borrow_utilization(price_field).unwrap();
+    ) -> Result<(ANA, ANA)> {
+        // an undercollateralized position pays the full debt fee until it is liquidated
+        let borrow_utilization = self
+            .get_nirv_borrow_utilization(price_field)
+            .unwrap_or(Decimal::ONE);
 
-        let total_rewards = self.calc_rewards_before_fees(config.reward_index);
+        let total_rewards = self.calc_rewards_before_fees(config.reward_index)?;
 
         let (rewards_less_fees, fees) =
             config.calc_nirv_debt_fee(total_rewards, borrow_utilization);
 
-        (rewards_less_fees, fees)
+        Ok((rewards_less_fees, fees))
     }
 
     /// Claim prana rewards
@@ -65,24 +116,42 @@ impl UserRewardV2 {
         config: &mut NirvCenterConfigV3,
         price_field: &PriceFieldV2,
         total_alms_staked: ALMS,
-    ) -> (ANA, ANA) {
-        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field);
+    ) -> Result<(ANA, ANA)> {
+        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field)?;
 
         let total_rewards = new_rewards + self.staged_pre_ana_rewards;
         let total_fees = new_fees + self.staged_pre_ana_fees;
 
-        config.collect_prana_fee(total_fees, total_alms_staked);
+        config.collect_prana_fee(total_fees, total_alms_staked)?;
 
         self.reset_rewards(config.reward_index);
 
-        (total_rewards, total_fees)
+        Ok((total_rewards, total_fees))
+    }
+
+    /// Can the signer act for the owner?
+    ///
+    /// The delegate can claim, compound and restake, but anything leaving
+    /// the position still only goes to the owner.
+    pub fn is_owner_or_delegate(&self, signer: Pubkey) -> bool {
+        signer == self.owner || (self.delegate != Pubkey::default() && signer == self.delegate)
+    }
+
+    /// Can the signer compound rewards for this account?
+    pub fn can_compound(&self, signer: Pubkey) -> bool {
+        self.is_owner_or_delegate(signer)
+            || (self.compound_keeper != Pubkey::default() && signer == self.compound_keeper)
     }
 
     /// Stage the un-claimed rewards from the central index
-    pub fn stage_rewards(&mut self, config: &NirvCenterConfigV3, price_field: &PriceFieldV2) {
+    pub fn stage_rewards(
+        &mut self,
+        config: &NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
         // get new rewards
         let central_reward_index = config.reward_index;
-        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field);
+        let (new_rewards, new_fees) = self.calc_rewards_and_fees(config, price_field)?;
 
         // stage them
         self.staged_pre_ana_rewards = self.staged_pre_ana_rewards + new_rewards;
@@ -90,6 +159,8 @@ impl UserRewardV2 {
 
         // update the index
         self.index = central_reward_index;
+
+        Ok(())
     }
 
     /// Add to staked amount
@@ -98,11 +169,38 @@ impl UserRewardV2 {
         amount: ANA,
         config: &NirvCenterConfigV3,
         price_field: &PriceFieldV2,
-    ) {
+    ) -> Result<()> {
         // stage the unclaimed rewards
-        self.stage_rewards(config, price_field);
+        self.stage_rewards(config, price_field)?;
 
         self.staked_amount += amount;
+
+        Ok(())
+    }
+
+    /// Move the stake, borrow and staged rewards of a v1 account into this one
+    ///
+    /// The v1 rewards are staged against the current index first, so nothing pending is lost.
+    pub fn migrate_from_v1(
+        &mut self,
+        v1: &mut UserReward,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        v1.stage_rewards(config, price_field)?;
+
+        self.stake_ana(v1.staked_amount, config, price_field)?;
+        self.borrowed_nirv += v1.borrowed_nirv;
+        config.add_borrowed_nirv(v1.borrowed_nirv);
+        self.staged_pre_ana_rewards += v1.staged_pre_ana_rewards;
+        self.staged_pre_ana_fees += v1.staged_pre_ana_fees;
+
+        v1.staked_amount = ANA::ZERO;
+        v1.borrowed_nirv = NIRV::ZERO;
+        v1.staged_pre_ana_rewards = ANA::ZERO;
+        v1.staged_pre_ana_fees = ANA::ZERO;
+
+        Ok(())
     }
 
     /// Unstake ANA
@@ -113,8 +211,98 @@ impl UserRewardV2 {
         price_field: &PriceFieldV2,
         total_alms_staked: ALMS,
     ) -> Result<(ANA, ANA)> {
+        self.withdraw_stake(amount, config, price_field)?;
+
+        // Calculate fee
+        let (amount_less_fee, fee) = config.collect_ana_unstake_fee(amount, total_alms_staked)?;
+
+        Ok((amount_less_fee, fee))
+    }
+
+    /// Move staked ANA into the unstake cooldown
+    ///
+    /// Pending ANA earns no rewards and doesn't back borrowed NIRV,
+    /// so a position with debt can't request one.
+    /// Adding to it restarts the cooldown.
+    pub fn request_unstake(
+        &mut self,
+        amount: ANA,
+        now: u64,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        // Without a cooldown it is an unstake with no fee
+        if config.unstake_cooldown_seconds == 0 {
+            return Err(ErrorCode::UnstakeCooldownNotSet.into());
+        }
+
+        // ANA waiting out the cooldown can't be seized by a liquidation
+        if self.borrowed_nirv.val > 0 {
+            return Err(ErrorCode::NirvDebtBlocksUnstakeRequest.into());
+        }
+
+        self.withdraw_stake(amount, config, price_field)?;
+
+        self.pending_unstake_amount += amount;
+        self.unstake_available_time = now.checked_add(config.unstake_cooldown_seconds).unwrap();
+        config.total_pending_unstake += amount;
+
+        Ok(())
+    }
+
+    /// Release the pending ANA once the cooldown is over
+    pub fn complete_unstake(&mut self, now: u64, config: &mut NirvCenterConfigV3) -> Result<ANA> {
+        if self.pending_unstake_amount == ANA::ZERO {
+            return Err(ErrorCode::NoPendingUnstake.into());
+        }
+
+        if now < self.unstake_available_time {
+            return Err(ErrorCode::UnstakeCooldownNotOver.into());
+        }
+
+        Ok(self.clear_pending_unstake(config))
+    }
+
+    /// Stake the pending ANA again
+    pub fn cancel_unstake(
+        &mut self,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        if self.pending_unstake_amount == ANA::ZERO {
+            return Err(ErrorCode::NoPendingUnstake.into());
+        }
+
+        let amount = self.clear_pending_unstake(config);
+        self.stake_ana(amount, config, price_field)?;
+
+        Ok(())
+    }
+
+    fn clear_pending_unstake(&mut self, config: &mut NirvCenterConfigV3) -> ANA {
+        let amount = self.pending_unstake_amount;
+
+        self.pending_unstake_amount = ANA::ZERO;
+        self.unstake_available_time = 0;
+        config.total_pending_unstake =
+            ANA::from_u64(config.total_pending_unstake.val.saturating_sub(amount.val));
+
+        amount
+    }
+
+    /// Remove from staked amount, as long as the rest still backs the borrowed NIRV
+    pub fn withdraw_stake(
+        &mut self,
+        amount: ANA,
+        config: &NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        if amount.val > self.unlocked_amount().val {
+            return Err(ErrorCode::InsufficientUnlockedANA.into());
+        }
+
         // stage the unclaimed rewards
-        self.stage_rewards(config, price_field);
+        self.stage_rewards(config, price_field)?;
 
         self.staked_amount -= amount;
 
@@ -124,10 +312,156 @@ impl UserRewardV2 {
             return Err(ErrorCode::InsufficientStakedANAToBackBorrowedNIRV.into());
         }
 
-        // Calculate fee
-        let (amount_less_fee, fee) = config.collect_ana_unstake_fee(amount, total_alms_staked);
+        Ok(())
+    }
 
-        Ok((amount_less_fee, fee))
+    /// Unstake ANA that is sold to repay borrowed NIRV
+    ///
+    /// Unlike unstake_ana, the remaining stake only has to back
+    /// the debt left after the repayment.
+    pub fn deleverage(
+        &mut self,
+        amount: ANA,
+        repay_amount: NIRV,
+        config: &NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        if amount.val > self.unlocked_amount().val {
+            return Err(ErrorCode::InsufficientUnlockedANA.into());
+        }
+
+        // stage the unclaimed rewards
+        self.stage_rewards(config, price_field)?;
+
+        self.staked_amount -= amount;
+        self.repay_nirv(repay_amount)?;
+
+        let borrow_limit = self.get_nirv_borrow_limit(price_field);
+
+        if self.borrowed_nirv.to_decimal() > borrow_limit {
+            return Err(ErrorCode::InsufficientStakedANAToBackBorrowedNIRV.into());
+        }
+
+        Ok(())
+    }
+
+    /// Staked ANA that is not locked
+    pub fn unlocked_amount(&self) -> ANA {
+        ANA::from_u64(
+            self.staked_amount
+                .val
+                .saturating_sub(self.locked_amount.val),
+        )
+    }
+
+    /// Extra reward weight from locked ANA
+    pub fn lock_boost(&self) -> ANA {
+        if self.locked_amount == ANA::ZERO {
+            return ANA::ZERO;
+        }
+
+        let boost = self
+            .locked_amount
+            .to_decimal()
+            .checked_mul(
+                self.lock_multiplier
+                    .to_decimal()
+                    .checked_sub(Decimal::ONE)
+                    .unwrap()
+                    .max(Decimal::ZERO),
+            )
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero);
+
+        ANA::from_decimal(boost)
+    }
+
+    /// Staked ANA weighted by the lock multiplier, used to share out rewards
+    pub fn effective_staked_amount(&self) -> ANA {
+        self.staked_amount + self.lock_boost()
+    }
+
+    /// Lock staked ANA for one of the lock tiers
+    ///
+    /// Adding to a lock relocks all of it for the new duration,
+    /// which can't end before the current lock does.
+    pub fn lock_ana(
+        &mut self,
+        amount: ANA,
+        duration_seconds: u64,
+        now: u64,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        if amount.val > self.unlocked_amount().val {
+            return Err(ErrorCode::InsufficientUnlockedANA.into());
+        }
+
+        self.extend_lock(duration_seconds, now, config, price_field)?;
+
+        let locked_amount = self.locked_amount + amount;
+        self.set_lock(locked_amount, self.lock_multiplier, config);
+
+        Ok(())
+    }
+
+    /// Relock the locked ANA for one of the lock tiers, starting now
+    pub fn extend_lock(
+        &mut self,
+        duration_seconds: u64,
+        now: u64,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        let multiplier = lock_multiplier(duration_seconds).ok_or(ErrorCode::InvalidLockDuration)?;
+        let lock_end_time = now.checked_add(duration_seconds).unwrap();
+
+        if lock_end_time < self.lock_end_time {
+            return Err(ErrorCode::LockCannotBeShortened.into());
+        }
+
+        // stage the rewards earned at the old weight
+        self.stage_rewards(config, price_field)?;
+
+        self.lock_end_time = lock_end_time;
+        let locked_amount = self.locked_amount;
+        self.set_lock(locked_amount, multiplier, config);
+
+        Ok(())
+    }
+
+    /// Unlock all the locked ANA once the lock has ended
+    pub fn unlock_ana(
+        &mut self,
+        now: u64,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<()> {
+        if now < self.lock_end_time {
+            return Err(ErrorCode::LockNotExpired.into());
+        }
+
+        // stage the rewards earned at the locked weight
+        self.stage_rewards(config, price_field)?;
+
+        self.set_lock(ANA::ZERO, CoarseNumber::default(), config);
+        self.lock_end_time = 0;
+
+        Ok(())
+    }
+
+    fn set_lock(
+        &mut self,
+        locked_amount: ANA,
+        multiplier: CoarseNumber,
+        config: &mut NirvCenterConfigV3,
+    ) {
+        config.sub_lock_boost(self.lock_boost());
+
+        self.locked_amount = locked_amount;
+        self.lock_multiplier = multiplier;
+
+        config.add_lock_boost(self.lock_boost());
     }
 
     /// The maximum amount of NIRV that can be borrowed
@@ -163,6 +497,110 @@ impl UserRewardV2 {
             .ok_or(ErrorCode::InvalidBorrowUtilization.into())
     }
 
+    /// The health of the NIRV loan, as the borrow limit over the borrowed NIRV
+    ///
+    /// A position with no debt is perfectly healthy, and a position
+    /// below one can be liquidated.
+    pub fn get_nirv_health_factor(&self, price_field: &PriceFieldV2) -> Decimal {
+        let borrowed_nirv = self.borrowed_nirv.to_decimal();
+
+        if borrowed_nirv == Decimal::ZERO {
+            return Decimal::MAX;
+        }
+
+        self.get_nirv_borrow_limit(price_field)
+            .checked_div(borrowed_nirv)
+            .unwrap()
+    }
+
+    /// Is more NIRV borrowed than the staked ANA can back?
+    pub fn is_nirv_liquidatable(&self, price_field: &PriceFieldV2) -> bool {
+        self.borrowed_nirv.to_decimal() > self.get_nirv_borrow_limit(price_field)
+    }
+
+    /// Repay part of an undercollateralized loan in exchange for staked ANA
+    ///
+    /// At most the close factor of the debt is repaid. The ANA seized is worth
+    /// the NIRV repaid plus the liquidation bonus, valued at the floor price,
+    /// though never more than the health factor allows, so the position is
+    /// no less healthy afterwards.
+    /// Returns the NIRV repaid, the ANA seized and the bad debt written off.
+    pub fn liquidate_nirv(
+        &mut self,
+        requested_amount: NIRV,
+        config: &mut NirvCenterConfigV3,
+        price_field: &PriceFieldV2,
+    ) -> Result<(NIRV, ANA, NIRV)> {
+        if !self.is_nirv_liquidatable(price_field) {
+            return Err(ErrorCode::NirvPositionNotLiquidatable.into());
+        }
+
+        let max_repay = self
+            .borrowed_nirv
+            .to_decimal()
+            .checked_mul(config.nirv_close_factor.into())
+            .unwrap()
+            .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::ToZero);
+        let repay = requested_amount.to_decimal().min(max_repay);
+
+        if repay == Decimal::ZERO {
+            return Err(ErrorCode::NirvLiquidationAmountZero.into());
+        }
+
+        // Below a health of 1 plus the bonus, seizing at the full bonus
+        // would take more than the position's share of the stake
+        let seize_multiplier = Decimal::ONE
+            .checked_add(config.nirv_liquidation_bonus.into())
+            .unwrap()
+            .min(self.get_nirv_health_factor(price_field));
+
+        // Since tokens out, round down
+        let seize_value = repay.checked_mul(seize_multiplier).unwrap();
+        let staked_ana = self.staked_amount.to_decimal();
+        let seized_ana = seize_value
+            .checked_div(price_field.floor_price.into())
+            .unwrap_or(staked_ana)
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::ToZero)
+            .min(staked_ana);
+
+        // when the stake runs out, the liquidator only repays what it covers
+        // Since tokens in, round up
+        let repay = if seized_ana < staked_ana {
+            repay
+        } else {
+            seized_ana
+                .checked_mul(price_field.floor_price.into())
+                .unwrap()
+                .checked_div(seize_multiplier)
+                .unwrap_or(Decimal::ZERO)
+                .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero)
+                .min(repay)
+        };
+
+        let repay = NIRV::from_decimal(repay);
+        let seized_ana = ANA::from_decimal(seized_ana);
+
+        self.borrowed_nirv -= repay;
+        self.staked_amount -= seized_ana;
+
+        // with nothing left to seize, the remaining debt is bad debt and written off
+        let bad_debt = if self.staked_amount == ANA::ZERO {
+            self.borrowed_nirv
+        } else {
+            NIRV::ZERO
+        };
+        config.sub_borrowed_nirv(bad_debt);
+        self.borrowed_nirv -= bad_debt;
+
+        // unlocked ANA is seized first, then the lock
+        if self.locked_amount.val > self.staked_amount.val {
+            let staked_amount = self.staked_amount;
+            self.set_lock(staked_amount, self.lock_multiplier, config);
+        }
+
+        Ok((repay, seized_ana, bad_debt))
+    }
+
     /// Add to borrowed nirv amount
     pub fn borrow_nirv(&mut self, amount: NIRV, price_field: &PriceFieldV2) -> Result<()> {
         self.borrowed_nirv += amount;
@@ -187,7 +625,72 @@ impl UserRewardV2 {
         Ok(())
     }
 
-    pub fn reset_rewards(&mut self, central_reward_index: PreciseNumber) {
+    /// Repay as much of the borrowed NIRV as the amount covers
+    ///
+    /// Returns the NIRV repaid
+    pub fn repay_nirv_up_to(&mut self, amount: NIRV) -> NIRV {
+        let repay = NIRV::from_u64(std::cmp::min(amount.val, self.borrowed_nirv.val));
+
+        self.borrowed_nirv -= repay;
+
+        repay
+    }
+
+    /// Interest owed on the borrowed NIRV since the user's borrow index
+    pub fn calc_nirv_interest(&self, central_borrow_index: PreciseNumber) -> NIRV {
+        if self.borrowed_nirv == NIRV::ZERO || self.nirv_borrow_index.val == 0 {
+            return NIRV::ZERO;
+        }
+
+        // Since debt owed, round up
+        let debt = self
+            .borrowed_nirv
+            .to_decimal()
+            .checked_mul(central_borrow_index.into())
+            .unwrap()
+            .checked_div(self.nirv_borrow_index.into())
+            .unwrap()
+            .round_dp_with_strategy(NIRV::SCALE, RoundingStrategy::AwayFromZero);
+
+        let interest = debt
+            .checked_sub(self.borrowed_nirv.into())
+            .unwrap()
+            .max(Decimal::ZERO);
+
+        NIRV::from_decimal(interest)
+    }
+
+    /// Add the interest accrued since the last update to the borrowed NIRV
+    ///
+    /// The interest is collected as a NIRV fee for ALMS stakers
+    pub fn accrue_nirv_interest(
+        &mut self,
+        config: &mut NirvCenterConfigV3,
+        total_alms_staked: ALMS,
+    ) -> Result<NIRV> {
+        let central_borrow_index = config.nirv_borrow_index;
+        let interest = self.calc_nirv_interest(central_borrow_index);
+
+        self.borrowed_nirv += interest;
+        self.nirv_borrow_index = central_borrow_index;
+
+        config.collect_nirv_fee(interest, total_alms_staked)?;
+
+        Ok(interest)
+    }
+
+    /// Nothing staked, locked, pending, borrowed or left to claim, and no streams joined
+    pub fn is_empty(&self) -> bool {
+        self.staked_amount == ANA::ZERO
+            && self.locked_amount == ANA::ZERO
+            && self.pending_unstake_amount == ANA::ZERO
+            && self.borrowed_nirv == NIRV::ZERO
+            && self.staged_pre_ana_rewards == ANA::ZERO
+            && self.staged_pre_ana_fees == ANA::ZERO
+            && self.stream_count == 0
+    }
+
+    pub fn reset_rewards(&mut self, central_reward_index: IndexNumber) {
         self.index = central_reward_index;
         self.staged_pre_ana_rewards = ANA::ZERO;
         self.staged_pre_ana_fees = ANA::ZERO;
@@ -197,44 +700,554 @@ impl UserRewardV2 {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::numbers::CoarseNumber;
+    use crate::state::MIN_UNSTAKE_COOLDOWN_SECONDS;
 
     #[test]
     fn calc_new_rewards_zero() {
         // 0.001
-        let index = PreciseNumber { val: 1_000_000_000 };
+        let index = IndexNumber {
+            val: 1_000_000_000_000_000,
+        };
 
         let user_reward = UserRewardV2 {
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
 
         let user_reward = UserRewardV2 {
-            index: PreciseNumber { val: 1_000_000 },
+            index: IndexNumber {
+                val: 1_000_000_000_000,
+            },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
     }
 
     #[test]
     fn calc_new_rewards() {
         // 0.001
-        let index = PreciseNumber { val: 2_000_000_000 };
+        let index = IndexNumber {
+            val: 2_000_000_000_000_000,
+        };
         let user_reward = UserRewardV2 {
-            index: PreciseNumber { val: 1_000_000_000 },
+            index: IndexNumber {
+                val: 1_000_000_000_000_000,
+            },
             staked_amount: ANA { val: 3_000_000 },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 3_000);
 
         let user_reward = UserRewardV2 {
-            index: PreciseNumber { val: 2_000_000_000 },
+            index: IndexNumber {
+                val: 2_000_000_000_000_000,
+            },
             staked_amount: ANA { val: 3_000_000 },
             ..Default::default()
         };
-        let rewards = user_reward.calc_rewards_before_fees(index);
+        let rewards = user_reward.calc_rewards_before_fees(index).unwrap();
         assert_eq!(rewards.val, 0);
     }
+
+    #[test]
+    fn calc_nirv_interest() {
+        let user_reward = UserRewardV2 {
+            borrowed_nirv: NIRV::new(100),
+            nirv_borrow_index: PreciseNumber::new(1),
+            ..Default::default()
+        };
+
+        let interest = user_reward.calc_nirv_interest(PreciseNumber::new(1));
+        assert_eq!(interest, NIRV::ZERO);
+
+        // 5% since the user's debt was last updated
+        let interest = user_reward.calc_nirv_interest(PreciseNumber {
+            val: 1_050_000_000_000,
+        });
+        assert_eq!(interest, NIRV::new(5));
+
+        // positions that never recorded an index owe nothing yet
+        let user_reward = UserRewardV2 {
+            borrowed_nirv: NIRV::new(100),
+            ..Default::default()
+        };
+        let interest = user_reward.calc_nirv_interest(PreciseNumber::new(2));
+        assert_eq!(interest, NIRV::ZERO);
+    }
+
+    #[test]
+    fn accrue_nirv_interest() {
+        let mut config = NirvCenterConfigV3 {
+            nirv_borrow_index: PreciseNumber {
+                val: 1_100_000_000_000,
+            },
+            ..Default::default()
+        };
+        let mut user_reward = UserRewardV2 {
+            borrowed_nirv: NIRV::new(10),
+            nirv_borrow_index: PreciseNumber::new(1),
+            ..Default::default()
+        };
+
+        let interest = user_reward
+            .accrue_nirv_interest(&mut config, ALMS::new(1))
+            .unwrap();
+        assert_eq!(interest, NIRV::new(1));
+        assert_eq!(user_reward.borrowed_nirv, NIRV::new(11));
+        assert_eq!(user_reward.nirv_borrow_index.val, 1_100_000_000_000);
+        assert_eq!(config.nirv_fee_index, IndexNumber::new(1));
+
+        // accruing again at the same index adds nothing
+        let interest = user_reward
+            .accrue_nirv_interest(&mut config, ALMS::new(1))
+            .unwrap();
+        assert_eq!(interest, NIRV::ZERO);
+        assert_eq!(user_reward.borrowed_nirv, NIRV::new(11));
+    }
+
+    fn floor_price_field(floor: u64) -> PriceFieldV2 {
+        PriceFieldV2 {
+            floor_price: PreciseNumber::new(floor),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nirv_health_factor() {
+        let pf = floor_price_field(1);
+
+        let user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            ..Default::default()
+        };
+        assert_eq!(user_reward.get_nirv_health_factor(&pf), Decimal::MAX);
+        assert!(!user_reward.is_nirv_liquidatable(&pf));
+
+        let user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(50),
+            ..Default::default()
+        };
+        assert_eq!(user_reward.get_nirv_health_factor(&pf), Decimal::new(2, 0));
+        assert!(!user_reward.is_nirv_liquidatable(&pf));
+
+        let user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(125),
+            ..Default::default()
+        };
+        assert_eq!(user_reward.get_nirv_health_factor(&pf), Decimal::new(8, 1));
+        assert!(user_reward.is_nirv_liquidatable(&pf));
+    }
+
+    #[test]
+    fn undercollateralized_rewards_pay_full_debt_fee() {
+        let pf = floor_price_field(1);
+        let config = NirvCenterConfigV3 {
+            reward_index: IndexNumber::new(1),
+            // 10%
+            nirv_debt_fee: CoarseNumber { val: 100_000 },
+            ..Default::default()
+        };
+        let user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(125),
+            ..Default::default()
+        };
+
+        let (rewards, fees) = user_reward.calc_rewards_and_fees(&config, &pf).unwrap();
+        assert_eq!(rewards, ANA::new(90));
+        assert_eq!(fees, ANA::new(10));
+    }
+
+    #[test]
+    fn liquidate_nirv() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            // 50%
+            nirv_close_factor: CoarseNumber { val: 500_000 },
+            // 5%
+            nirv_liquidation_bonus: CoarseNumber { val: 50_000 },
+            ..Default::default()
+        };
+
+        let mut healthy = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(100),
+            ..Default::default()
+        };
+        assert!(healthy
+            .liquidate_nirv(NIRV::new(10), &mut config, &pf)
+            .is_err());
+
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(125),
+            ..Default::default()
+        };
+        assert_eq!(user_reward.get_nirv_health_factor(&pf), Decimal::new(8, 1));
+
+        // repay is capped by the close factor, and the bonus by the health
+        let (repaid, seized, bad_debt) = user_reward
+            .liquidate_nirv(NIRV::new(100), &mut config, &pf)
+            .unwrap();
+        assert_eq!(repaid, NIRV::from_u64(62_500_000));
+        assert_eq!(seized, ANA::new(50));
+        assert_eq!(bad_debt, NIRV::ZERO);
+        assert_eq!(user_reward.borrowed_nirv, NIRV::from_u64(62_500_000));
+        assert_eq!(user_reward.staked_amount, ANA::new(50));
+
+        // so the position is no less healthy than before
+        assert_eq!(user_reward.get_nirv_health_factor(&pf), Decimal::new(8, 1));
+    }
+
+    #[test]
+    fn liquidate_nirv_bad_debt() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            nirv_close_factor: CoarseNumber { val: 500_000 },
+            nirv_liquidation_bonus: CoarseNumber { val: 50_000 },
+            total_borrowed_nirv: NIRV::new(5),
+            ..Default::default()
+        };
+
+        // nothing is left to seize
+        let mut user_reward = UserRewardV2 {
+            borrowed_nirv: NIRV::new(5),
+            ..Default::default()
+        };
+
+        let (repaid, seized, bad_debt) = user_reward
+            .liquidate_nirv(NIRV::new(5), &mut config, &pf)
+            .unwrap();
+        assert_eq!(repaid, NIRV::ZERO);
+        assert_eq!(seized, ANA::ZERO);
+
+        // so the debt is written off
+        assert_eq!(bad_debt, NIRV::new(5));
+        assert_eq!(user_reward.borrowed_nirv, NIRV::ZERO);
+        assert_eq!(config.total_borrowed_nirv, NIRV::ZERO);
+    }
+
+    #[test]
+    fn deleverage() {
+        let pf = floor_price_field(1);
+        let config = NirvCenterConfigV3 {
+            ..Default::default()
+        };
+
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(200),
+            borrowed_nirv: NIRV::new(150),
+            ..Default::default()
+        };
+
+        // the debt is repaid before the smaller stake has to back it
+        user_reward
+            .deleverage(ANA::new(100), NIRV::new(90), &config, &pf)
+            .unwrap();
+        assert_eq!(user_reward.staked_amount, ANA::new(100));
+        assert_eq!(user_reward.borrowed_nirv, NIRV::new(60));
+
+        // the remaining stake still has to back the remaining debt
+        assert!(user_reward
+            .deleverage(ANA::new(50), NIRV::new(5), &config, &pf)
+            .is_err());
+    }
+
+    #[test]
+    fn repay_nirv_up_to() {
+        let mut user_reward = UserRewardV2 {
+            borrowed_nirv: NIRV::new(10),
+            ..Default::default()
+        };
+
+        assert_eq!(user_reward.repay_nirv_up_to(NIRV::new(4)), NIRV::new(4));
+        assert_eq!(user_reward.borrowed_nirv, NIRV::new(6));
+
+        // never repays more than is owed
+        assert_eq!(user_reward.repay_nirv_up_to(NIRV::new(10)), NIRV::new(6));
+        assert_eq!(user_reward.borrowed_nirv, NIRV::ZERO);
+    }
+
+    #[test]
+    fn can_compound() {
+        let owner = Pubkey::new_unique();
+        let keeper = Pubkey::new_unique();
+
+        let mut user_reward = UserRewardV2 {
+            owner,
+            ..Default::default()
+        };
+        assert!(user_reward.can_compound(owner));
+        assert!(!user_reward.can_compound(keeper));
+        assert!(!user_reward.can_compound(Pubkey::default()));
+
+        user_reward.compound_keeper = keeper;
+        assert!(user_reward.can_compound(owner));
+        assert!(user_reward.can_compound(keeper));
+    }
+
+    #[test]
+    fn is_owner_or_delegate() {
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let keeper = Pubkey::new_unique();
+
+        let mut user_reward = UserRewardV2 {
+            owner,
+            compound_keeper: keeper,
+            ..Default::default()
+        };
+        assert!(user_reward.is_owner_or_delegate(owner));
+        assert!(!user_reward.is_owner_or_delegate(delegate));
+        assert!(!user_reward.is_owner_or_delegate(Pubkey::default()));
+
+        user_reward.delegate = delegate;
+        assert!(user_reward.is_owner_or_delegate(delegate));
+        assert!(user_reward.can_compound(delegate));
+
+        // the keeper can only compound
+        assert!(!user_reward.is_owner_or_delegate(keeper));
+        assert!(user_reward.can_compound(keeper));
+    }
+
+    #[test]
+    fn lock_ana() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            ..Default::default()
+        };
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            ..Default::default()
+        };
+        let month = LOCK_TIERS[0].0;
+
+        assert!(user_reward
+            .lock_ana(ANA::new(10), 12345, 0, &mut config, &pf)
+            .is_err());
+        assert!(user_reward
+            .lock_ana(ANA::new(101), month, 0, &mut config, &pf)
+            .is_err());
+
+        // 1.1x on 50 locked
+        user_reward
+            .lock_ana(ANA::new(50), month, 0, &mut config, &pf)
+            .unwrap();
+        assert_eq!(user_reward.lock_boost(), ANA::new(5));
+        assert_eq!(user_reward.effective_staked_amount(), ANA::new(105));
+        assert_eq!(config.total_lock_boost, ANA::new(5));
+        assert_eq!(user_reward.unlocked_amount(), ANA::new(50));
+
+        // locked ANA can't be unstaked
+        assert!(user_reward
+            .unstake_ana(ANA::new(51), &mut config, &pf, ALMS::ZERO)
+            .is_err());
+
+        // 2x for a year
+        user_reward
+            .extend_lock(12 * month, 10, &mut config, &pf)
+            .unwrap();
+        assert_eq!(config.total_lock_boost, ANA::new(50));
+        assert_eq!(user_reward.lock_end_time, 10 + 12 * month);
+
+        // can't shorten the lock
+        assert!(user_reward
+            .lock_ana(ANA::new(10), month, 20, &mut config, &pf)
+            .is_err());
+        assert!(user_reward.unlock_ana(20, &mut config, &pf).is_err());
+
+        user_reward
+            .unlock_ana(10 + 12 * month, &mut config, &pf)
+            .unwrap();
+        assert_eq!(user_reward.locked_amount, ANA::ZERO);
+        assert_eq!(config.total_lock_boost, ANA::ZERO);
+        assert_eq!(user_reward.effective_staked_amount(), ANA::new(100));
+    }
+
+    #[test]
+    fn lock_boost_stops_after_expiry() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            ..Default::default()
+        };
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            ..Default::default()
+        };
+        let month = LOCK_TIERS[0].0;
+
+        // 2x on all of it for a year
+        user_reward
+            .lock_ana(ANA::new(100), 12 * month, 0, &mut config, &pf)
+            .unwrap();
+        assert_eq!(
+            user_reward
+                .calc_rewards_before_fees(IndexNumber::new(1))
+                .unwrap(),
+            ANA::new(200)
+        );
+
+        // a keeper unlocks it as soon as it ends
+        assert!(user_reward
+            .unlock_ana(12 * month - 1, &mut config, &pf)
+            .is_err());
+        user_reward
+            .unlock_ana(12 * month, &mut config, &pf)
+            .unwrap();
+
+        assert_eq!(user_reward.lock_boost(), ANA::ZERO);
+        assert_eq!(config.total_lock_boost, ANA::ZERO);
+        assert_eq!(
+            user_reward
+                .calc_rewards_before_fees(IndexNumber::new(1))
+                .unwrap(),
+            ANA::new(100)
+        );
+    }
+
+    #[test]
+    fn locked_rewards() {
+        let user_reward = UserRewardV2 {
+            staked_amount: ANA::new(3),
+            locked_amount: ANA::new(2),
+            lock_multiplier: CoarseNumber { val: 1_500_000 },
+            ..Default::default()
+        };
+
+        // 3 staked plus half of the 2 locked
+        let rewards = user_reward
+            .calc_rewards_before_fees(IndexNumber::new(1))
+            .unwrap();
+        assert_eq!(rewards, ANA::new(4));
+    }
+
+    #[test]
+    fn unstake_cooldown() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            unstake_cooldown_seconds: 100,
+            ..Default::default()
+        };
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            ..Default::default()
+        };
+
+        user_reward
+            .request_unstake(ANA::new(30), 0, &mut config, &pf)
+            .unwrap();
+        assert_eq!(user_reward.staked_amount, ANA::new(70));
+        assert_eq!(user_reward.pending_unstake_amount, ANA::new(30));
+        assert_eq!(config.total_pending_unstake, ANA::new(30));
+
+        assert!(user_reward.complete_unstake(99, &mut config).is_err());
+
+        user_reward.cancel_unstake(&mut config, &pf).unwrap();
+        assert_eq!(user_reward.staked_amount, ANA::new(100));
+        assert_eq!(config.total_pending_unstake, ANA::ZERO);
+        assert!(user_reward.complete_unstake(1_000, &mut config).is_err());
+
+        user_reward
+            .request_unstake(ANA::new(20), 50, &mut config, &pf)
+            .unwrap();
+        let released = user_reward.complete_unstake(150, &mut config).unwrap();
+        assert_eq!(released, ANA::new(20));
+        assert_eq!(user_reward.staked_amount, ANA::new(80));
+        assert_eq!(user_reward.pending_unstake_amount, ANA::ZERO);
+        assert_eq!(config.total_pending_unstake, ANA::ZERO);
+
+        // pending ANA doesn't back a loan, so none can be queued while borrowing
+        user_reward.borrowed_nirv = NIRV::new(1);
+        assert!(user_reward
+            .request_unstake(ANA::new(1), 200, &mut config, &pf)
+            .is_err());
+    }
+
+    #[test]
+    fn unstake_cooldown_same_timestamp() {
+        let pf = floor_price_field(1);
+        let mut config = NirvCenterConfigV3 {
+            unstake_cooldown_seconds: MIN_UNSTAKE_COOLDOWN_SECONDS,
+            ..Default::default()
+        };
+        let mut user_reward = UserRewardV2 {
+            staked_amount: ANA::new(100),
+            ..Default::default()
+        };
+
+        user_reward
+            .request_unstake(ANA::new(100), 500, &mut config, &pf)
+            .unwrap();
+        assert!(user_reward.complete_unstake(500, &mut config).is_err());
+        assert_eq!(user_reward.pending_unstake_amount, ANA::new(100));
+
+        // no cooldown, no request
+        user_reward.cancel_unstake(&mut config, &pf).unwrap();
+        config.unstake_cooldown_seconds = 0;
+        assert!(user_reward
+            .request_unstake(ANA::new(100), 500, &mut config, &pf)
+            .is_err());
+        assert_eq!(user_reward.staked_amount, ANA::new(100));
+        assert_eq!(user_reward.pending_unstake_amount, ANA::ZERO);
+    }
+
+    #[test]
+    fn migrate_from_v1() {
+        let mut config = NirvCenterConfigV3 {
+            reward_index: IndexNumber::new(2),
+            total_borrowed_nirv: NIRV::new(5),
+            ..Default::default()
+        };
+        let price_field = floor_price_field(1);
+
+        let mut v1 = UserReward {
+            index: PreciseNumber::from_decimal(Decimal::ONE),
+            staked_amount: ANA::new(100),
+            borrowed_nirv: NIRV::new(20),
+            staged_pre_ana_rewards: ANA::new(5),
+            staged_pre_ana_fees: ANA::new(1),
+            ..Default::default()
+        };
+        let mut v2 = UserRewardV2 {
+            index: config.reward_index,
+            staked_amount: ANA::new(10),
+            ..Default::default()
+        };
+
+        v2.migrate_from_v1(&mut v1, &mut config, &price_field)
+            .unwrap();
+
+        // 100 pending on the v1 stake, on top of what was staged
+        assert_eq!(v2.staked_amount, ANA::new(110));
+        assert_eq!(v2.staged_pre_ana_rewards, ANA::new(105));
+        assert_eq!(v2.staged_pre_ana_fees, ANA::new(1));
+        assert_eq!(v2.index, config.reward_index);
+
+        // the v1 borrow now counts toward the total
+        assert_eq!(v2.borrowed_nirv, NIRV::new(20));
+        assert_eq!(config.total_borrowed_nirv, NIRV::new(25));
+
+        assert_eq!(v1.staked_amount, ANA::ZERO);
+        assert_eq!(v1.borrowed_nirv, NIRV::ZERO);
+        assert_eq!(v1.staged_pre_ana_rewards, ANA::ZERO);
+    }
+
+    #[test]
+    fn is_empty() {
+        let mut user_reward = UserRewardV2::default();
+        assert!(user_reward.is_empty());
+
+        user_reward.staged_pre_ana_fees = ANA::from_u64(1);
+        assert!(!user_reward.is_empty());
+
+        user_reward.staged_pre_ana_fees = ANA::ZERO;
+        user_reward.stream_count = 1;
+        assert!(!user_reward.is_empty());
+    }
 }
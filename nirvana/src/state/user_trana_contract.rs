@@ -1,10 +1,11 @@
-use crate::numbers::{ArbitraryNumber, ANA};
+use crate::numbers::{ArbitraryNumber, CoarseNumber, Decimalable, ANA};
 use anchor_lang::prelude::*;
+use rust_decimal::prelude::*;
 
 #[account]
 #[derive(Default, Debug)]
 pub struct UserTranaContract {
-    /// owner of the bond
+    /// Wallet that created the account and gets its rent back, the holder of the mint's token owns the bond
     pub user: Pubkey,
 
     /// Is the bond open for re-use
@@ -27,6 +28,24 @@ pub struct UserTranaContract {
 
     /// when will the bond end
     pub end_time: i64,
+
+    /// One-of-one token representing the bond, whoever holds it can redeem
+    pub mint: Pubkey,
+
+    /// Should the vested ANA be staked when redeemed, rather than sent to the wallet?
+    pub auto_stake: bool,
+
+    /// Nothing vests before this
+    pub cliff_time: i64,
+
+    /// Vested ANA is released in steps this long, zero for a linear release
+    pub release_step_seconds: u64,
+
+    /// Can the unvested ANA be redeemed now, less the penalty?
+    pub early_exit_enabled: bool,
+
+    /// Share of the unvested ANA kept on an early exit
+    pub early_exit_penalty: CoarseNumber,
 }
 
 impl UserTranaContract {
@@ -42,6 +61,10 @@ impl UserTranaContract {
             return ANA::ZERO;
         }
 
+        if now < self.cliff_time {
+            return ANA::ZERO;
+        }
+
         // Get the amount that can be redeemed at this time
         let end = self.end_time;
         let start = self.start_time;
@@ -50,6 +73,13 @@ impl UserTranaContract {
         // let progress = Decimal::from_u64(progress).unwrap();
 
         let span = end.checked_sub(start).unwrap().unsigned_abs();
+
+        // Only whole steps are released until the end
+        let progress = if self.release_step_seconds != 0 && progress < span {
+            progress - progress % self.release_step_seconds
+        } else {
+            progress
+        };
         // let span = Decimal::from_u64(span).unwrap();
 
         let mut redeemable = amount
@@ -81,6 +111,30 @@ impl UserTranaContract {
         ANA::from_u64(left_to_redeem)
     }
 
+    /// Can the account be closed? Not in use and everything redeemed
+    pub fn can_close(&self) -> bool {
+        self.available && self.redeemed_amount == self.amount_ana
+    }
+
+    /// Redeem everything now, the unvested ANA less the penalty
+    ///
+    /// Returns what is paid out and the penalty.
+    pub fn exit_early(&mut self, now: i64) -> (ANA, ANA) {
+        let vested = self.get_left_to_redeem(now);
+        let unvested = self.amount_ana - self.redeemed_amount - vested;
+
+        let penalty = unvested
+            .to_decimal()
+            .checked_mul(self.early_exit_penalty.to_decimal())
+            .unwrap()
+            .round_dp_with_strategy(ANA::SCALE, RoundingStrategy::AwayFromZero);
+        let penalty = ANA::from_decimal(penalty);
+
+        self.update_redeemed(vested + unvested);
+
+        (vested + unvested - penalty, penalty)
+    }
+
     /// Given an amount left to redeem, update the user trana
     pub fn update_redeemed(&mut self, left_to_redeem: ANA) {
         let redeemed_amount = self
@@ -132,4 +186,75 @@ mod test {
         assert_eq!(b.get_left_to_redeem(31999).val, 0);
         assert_eq!(b.get_left_to_redeem(16000).val, 0);
     }
+
+    #[test]
+    fn test_can_close() {
+        let mut b = UserTranaContract {
+            available: true,
+            ..Default::default()
+        };
+        assert!(b.can_close());
+
+        b.available = false;
+        b.amount_ana = ANA::ONE;
+        assert!(!b.can_close());
+
+        b.update_redeemed(ANA::ONE);
+        assert!(b.can_close());
+    }
+
+    #[test]
+    fn test_get_left_redeem_with_cliff() {
+        let b = UserTranaContract {
+            amount_ana: ANA::ONE,
+            start_time: 0,
+            end_time: 32000,
+            cliff_time: 8000,
+            ..Default::default()
+        };
+
+        assert_eq!(b.get_left_to_redeem(7999), ANA::ZERO);
+        assert_eq!(b.get_left_to_redeem(8000), ANA::from_u64(250_000));
+        assert_eq!(b.get_left_to_redeem(32000), ANA::ONE);
+    }
+
+    #[test]
+    fn test_get_left_redeem_stepped() {
+        let b = UserTranaContract {
+            amount_ana: ANA::ONE,
+            start_time: 0,
+            end_time: 30000,
+            release_step_seconds: 7000,
+            ..Default::default()
+        };
+
+        assert_eq!(b.get_left_to_redeem(6999), ANA::ZERO);
+        assert_eq!(b.get_left_to_redeem(7000), ANA::from_u64(233_333));
+        assert_eq!(b.get_left_to_redeem(13999), ANA::from_u64(233_333));
+        assert_eq!(b.get_left_to_redeem(28000), ANA::from_u64(933_333));
+
+        // the last partial step is released at the end
+        assert_eq!(b.get_left_to_redeem(29999), ANA::from_u64(933_333));
+        assert_eq!(b.get_left_to_redeem(30000), ANA::ONE);
+    }
+
+    #[test]
+    fn test_exit_early() {
+        let mut b = UserTranaContract {
+            amount_ana: ANA::new(100),
+            redeemed_amount: ANA::new(10),
+            start_time: 0,
+            end_time: 1000,
+            // 50%
+            early_exit_penalty: CoarseNumber { val: 500_000 },
+            ..Default::default()
+        };
+
+        // 40 vested, 10 of it redeemed, and half of the 60 unvested kept
+        let (payout, penalty) = b.exit_early(400);
+        assert_eq!(payout, ANA::new(60));
+        assert_eq!(penalty, ANA::new(30));
+        assert_eq!(b.redeemed_amount, ANA::new(100));
+        assert!(b.available);
+    }
 }
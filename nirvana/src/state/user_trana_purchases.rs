@@ -0,0 +1,18 @@
+use crate::numbers::ANA;
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(Default, Debug)]
+/// How much ANA a user has bought from a trANA bond, for its per-user limit
+pub struct UserTranaPurchases {
+    /// Link to trana metadata
+    pub trana_meta: Pubkey,
+
+    /// The buyer
+    pub user: Pubkey,
+
+    /// Total ANA bought, before fees
+    pub ana_bought: ANA,
+
+    pub bump: u8,
+}